@@ -0,0 +1,448 @@
+//! Receives the themes broadcast by the iced theme viewer, so that a running
+//! application can be restyled while a theme is being tuned.
+//!
+//! Add a subscription, preferably only to debug builds, and use the themes
+//! it produces:
+//!
+//! ```ignore
+//! fn subscription(&self) -> Subscription<Message> {
+//!     iced_theme_client::subscription(iced_theme_client::DEFAULT_ADDRESS)
+//!         .map(Message::ThemeChanged)
+//! }
+//! ```
+//!
+//! The viewer sends one theme per line, as the JSON of its theme files.
+use iced::{
+    Color, Subscription, Theme,
+    futures::{SinkExt, channel::mpsc, executor::block_on},
+    theme::{
+        Palette,
+        palette::{Background, Danger, Extended, Pair, Primary, Secondary, Success},
+    },
+};
+use serde::Deserialize;
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+    net::TcpStream,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::PathBuf};
+
+/// The address the viewer broadcasts on unless told otherwise.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// How long to wait before connecting again after losing the viewer.
+const RETRY: Duration = Duration::from_secs(1);
+
+/// How often the subscription stops waiting for a theme to check whether
+/// it is still needed.
+const POLL: Duration = Duration::from_millis(500);
+
+/// Either a TCP address such as `127.0.0.1:7878`, or a Unix socket path
+/// prefixed with `unix:`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Address {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = io::Error;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let address = address.trim();
+
+        if let Some(path) = address.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Address::Unix(PathBuf::from(path)));
+
+            #[cfg(not(unix))]
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unix sockets are not supported here: {path}"),
+            ));
+        }
+
+        if address.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty address"));
+        }
+
+        Ok(Address::Tcp(address.to_owned()))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(address) => write!(f, "{address}"),
+            #[cfg(unix)]
+            Address::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// An open connection to the viewer, producing every theme it sends.
+pub struct Connection {
+    reader: BufReader<Box<dyn Read + Send>>,
+    /// The part of a line read so far.
+    line: Vec<u8>,
+}
+
+impl Connection {
+    pub fn connect(address: &Address) -> io::Result<Self> {
+        Self::open(address, None)
+    }
+
+    /// Connects, giving up on each read after `timeout` with an error which
+    /// is [`io::ErrorKind::WouldBlock`] or [`io::ErrorKind::TimedOut`].
+    fn open(address: &Address, timeout: Option<Duration>) -> io::Result<Self> {
+        let stream: Box<dyn Read + Send> = match address {
+            Address::Tcp(address) => {
+                let stream = TcpStream::connect(address)?;
+                stream.set_read_timeout(timeout)?;
+
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(timeout)?;
+
+                Box::new(stream)
+            }
+        };
+
+        Ok(Self {
+            reader: BufReader::new(stream),
+            line: Vec::new(),
+        })
+    }
+}
+
+impl Iterator for Connection {
+    /// A theme, or an error which is [`io::ErrorKind::InvalidData`] when a
+    /// single line could not be decoded.
+    type Item = io::Result<Theme>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // What was read before an error is kept for the next call
+        match self.reader.read_until(b'\n', &mut self.line) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line = std::mem::take(&mut self.line);
+
+                Some(
+                    String::from_utf8(line)
+                        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+                        .and_then(|line| decode(&line)),
+                )
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+/// Decodes a single theme sent by the viewer.
+pub fn decode(line: &str) -> io::Result<Theme> {
+    let message: Message = serde_json::from_str(line)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    Ok(message.into_theme())
+}
+
+/// Whether reading gave up after the timeout of [`Connection::open`].
+fn timed_out(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Produces every theme the viewer broadcasts at `address`, connecting
+/// again whenever the viewer is restarted.
+pub fn subscription(address: impl Into<String>) -> Subscription<Theme> {
+    let address: String = address.into();
+
+    Subscription::run_with_id(
+        address.clone(),
+        iced::stream::channel(16, move |output| async move {
+            let _ = thread::spawn(move || forward(&address, output));
+
+            std::future::pending::<()>().await;
+        }),
+    )
+}
+
+/// Sends every theme broadcast at `address` to `output` until it is
+/// closed, connecting again whenever the viewer is restarted.
+fn forward(address: &str, mut output: mpsc::Sender<Theme>) {
+    loop {
+        let connection = address
+            .parse()
+            .and_then(|address| Connection::open(&address, Some(POLL)));
+
+        for theme in connection.into_iter().flatten() {
+            match theme {
+                // Waiting for room, rather than dropping themes when the
+                // application falls behind, keeps the last theme sent from
+                // being lost
+                Ok(theme) => {
+                    if block_on(output.send(theme)).is_err() {
+                        return;
+                    }
+                }
+                // The subscription was dropped while waiting
+                Err(error) if timed_out(&error) => {
+                    if output.is_closed() {
+                        return;
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => {}
+                Err(_) => break,
+            }
+        }
+
+        if output.is_closed() {
+            return;
+        }
+
+        thread::sleep(RETRY);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    name: String,
+    is_dark: bool,
+    background: Group,
+    primary: Group,
+    secondary: Group,
+    success: Group,
+    danger: Group,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct Group {
+    base: Colors,
+    weak: Colors,
+    strong: Colors,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct Colors {
+    #[serde(deserialize_with = "hex")]
+    color: Color,
+    #[serde(deserialize_with = "hex")]
+    text: Color,
+}
+
+impl From<Colors> for Pair {
+    fn from(colors: Colors) -> Self {
+        Pair {
+            color: colors.color,
+            text: colors.text,
+        }
+    }
+}
+
+impl Message {
+    fn into_theme(self) -> Theme {
+        let extended = Extended {
+            background: Background {
+                base: self.background.base.into(),
+                weak: self.background.weak.into(),
+                strong: self.background.strong.into(),
+            },
+            primary: Primary {
+                base: self.primary.base.into(),
+                weak: self.primary.weak.into(),
+                strong: self.primary.strong.into(),
+            },
+            secondary: Secondary {
+                base: self.secondary.base.into(),
+                weak: self.secondary.weak.into(),
+                strong: self.secondary.strong.into(),
+            },
+            success: Success {
+                base: self.success.base.into(),
+                weak: self.success.weak.into(),
+                strong: self.success.strong.into(),
+            },
+            danger: Danger {
+                base: self.danger.base.into(),
+                weak: self.danger.weak.into(),
+                strong: self.danger.strong.into(),
+            },
+            is_dark: self.is_dark,
+        };
+
+        let palette = Palette {
+            background: extended.background.base.color,
+            text: extended.background.base.text,
+            primary: extended.primary.base.color,
+            success: extended.success.base.color,
+            danger: extended.danger.base.color,
+        };
+
+        Theme::custom_with_fn(self.name, palette, move |_| extended)
+    }
+}
+
+fn hex<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+
+    // `Color::parse` slices by bytes, and panics on other characters
+    hex.is_ascii()
+        .then(|| Color::parse(&hex))
+        .flatten()
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid color {hex:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r##"{"name":"Sample","is_dark":true,"background":{"base":{"color":"#101010","text":"#f0f0f0"},"weak":{"color":"#202020","text":"#f0f0f0"},"strong":{"color":"#303030","text":"#ffffff"}},"primary":{"base":{"color":"#3366ff","text":"#ffffff"},"weak":{"color":"#223366","text":"#ffffff"},"strong":{"color":"#5588ff","text":"#000000"}},"secondary":{"base":{"color":"#404040","text":"#ffffff"},"weak":{"color":"#505050","text":"#ffffff"},"strong":{"color":"#606060","text":"#ffffff"}},"success":{"base":{"color":"#22aa44","text":"#000000"},"weak":{"color":"#115522","text":"#ffffff"},"strong":{"color":"#33cc55","text":"#000000"}},"danger":{"base":{"color":"#dd3333","text":"#ffffff"},"weak":{"color":"#661919","text":"#ffffff"},"strong":{"color":"#ff5555","text":"#000000"}},"locked":[]}"##;
+
+    #[test]
+    fn decodes_a_theme() {
+        let theme = decode(LINE).unwrap();
+        let extended = theme.extended_palette();
+
+        assert_eq!(theme.to_string(), "Sample");
+        assert!(extended.is_dark);
+        assert_eq!(
+            extended.primary.base.color,
+            Color::from_rgb8(0x33, 0x66, 0xff)
+        );
+        assert_eq!(
+            extended.danger.strong.text,
+            Color::from_rgb8(0x00, 0x00, 0x00)
+        );
+        assert_eq!(theme.palette().text, Color::from_rgb8(0xf0, 0xf0, 0xf0));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for line in [
+            LINE.replace("#3366ff", "aéée"),
+            LINE.replace("#3366ff", "#3366fg"),
+            LINE.replace(r#""is_dark":true,"#, ""),
+            LINE[..LINE.len() / 2].to_owned(),
+            "not json".to_owned(),
+        ] {
+            let error = decode(&line).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{line}");
+        }
+    }
+
+    /// Reads its chunks one at a time, timing out after each.
+    struct Chunks(Vec<Vec<u8>>, bool);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1 = !self.1;
+
+            if self.1 {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+
+            let chunk = self.0.remove(0);
+            buf[..chunk.len()].copy_from_slice(&chunk);
+
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn keeps_lines_read_across_timeouts() {
+        let line = format!("{LINE}\n");
+        let (first, second) = line.as_bytes().split_at(100);
+
+        let mut connection = Connection {
+            reader: BufReader::new(Box::new(Chunks(
+                vec![first.to_vec(), second.to_vec()],
+                false,
+            ))),
+            line: Vec::new(),
+        };
+
+        let mut themes = Vec::new();
+
+        for theme in connection.by_ref() {
+            match theme {
+                Ok(theme) => themes.push(theme),
+                Err(error) => assert!(timed_out(&error)),
+            }
+        }
+
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].to_string(), "Sample");
+    }
+
+    #[test]
+    fn bursts_end_on_the_last_theme() {
+        use iced::futures::StreamExt;
+        use std::{io::Write, net::TcpListener, sync::mpsc as std_mpsc};
+
+        const COUNT: usize = 40;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (output, themes) = mpsc::channel(16);
+
+        let _ = thread::spawn(move || forward(&address, output));
+
+        let (mut stream, _) = listener.accept().unwrap();
+
+        for index in 0..COUNT {
+            writeln!(
+                stream,
+                "{}",
+                LINE.replace("Sample", &format!("Theme {index}"))
+            )
+            .unwrap();
+        }
+
+        // Let the channel fill up before anything is taken from it
+        thread::sleep(Duration::from_millis(200));
+
+        let (names, received) = std_mpsc::channel();
+        let _ = thread::spawn(move || {
+            let themes: Vec<Theme> = block_on(themes.take(COUNT).collect());
+            let _ = names.send(themes.iter().map(Theme::to_string).collect::<Vec<_>>());
+        });
+
+        let names = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        let expected: Vec<String> = (0..COUNT).map(|index| format!("Theme {index}")).collect();
+
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn parses_addresses() {
+        assert_eq!(
+            DEFAULT_ADDRESS.parse::<Address>().unwrap(),
+            Address::Tcp(DEFAULT_ADDRESS.to_owned())
+        );
+        assert!(" ".parse::<Address>().is_err());
+
+        #[cfg(unix)]
+        {
+            let address: Address = "unix:/tmp/themes.sock".parse().unwrap();
+
+            assert_eq!(address, Address::Unix(PathBuf::from("/tmp/themes.sock")));
+            assert_eq!(address.to_string(), "unix:/tmp/themes.sock");
+        }
+    }
+}
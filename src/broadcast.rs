@@ -0,0 +1,257 @@
+//! Publishes the current palette to running iced applications, which
+//! receive it with the `iced_theme_client` crate.
+use crate::theme_file::{self, ThemeFile};
+use iced_theme_client::Address;
+use std::{
+    fmt,
+    io::{self, Write},
+    net::TcpListener,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+
+/// How often the listener checks for new clients.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a client may take to receive a theme before it is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub struct Server {
+    address: Address,
+    writer: Sender<Event>,
+    stop: Arc<AtomicBool>,
+}
+
+/// What the writer thread is told about.
+enum Event {
+    Client(Box<dyn Write + Send>),
+    Theme(Box<ThemeFile>),
+}
+
+/// Sends every theme to the clients, away from the UI thread since a slow
+/// client can take up to [`WRITE_TIMEOUT`] to be dropped.
+fn write(events: &Receiver<Event>) {
+    // The last line sent, which new clients receive right away
+    let mut latest: Option<String> = None;
+    let mut clients: Vec<Box<dyn Write + Send>> = Vec::new();
+
+    while let Ok(event) = events.recv() {
+        // Only the newest of the themes queued meanwhile is worth sending
+        let mut theme = None;
+
+        for event in std::iter::once(event).chain(events.try_iter()) {
+            match event {
+                Event::Client(mut client) => {
+                    let welcomed = latest
+                        .as_ref()
+                        .is_none_or(|latest| client.write_all(latest.as_bytes()).is_ok());
+
+                    if welcomed {
+                        clients.push(client);
+                    }
+                }
+                Event::Theme(file) => theme = Some(file),
+            }
+        }
+
+        let Some(mut line) = theme.and_then(|file| theme_file::to_line(&file).ok()) else {
+            continue;
+        };
+        line.push('\n');
+
+        if latest.as_ref() != Some(&line) {
+            clients.retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+            latest = Some(line);
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(address: &Address) -> io::Result<Self> {
+        let listener = match address {
+            Address::Tcp(address) => Listener::Tcp(TcpListener::bind(address)?),
+            #[cfg(unix)]
+            Address::Unix(path) => Listener::Unix(UnixListener::bind(path)?),
+        };
+
+        match &listener {
+            Listener::Tcp(listener) => listener.set_nonblocking(true)?,
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.set_nonblocking(true)?,
+        }
+
+        Ok(listener)
+    }
+
+    fn accept(&self) -> io::Result<Box<dyn Write + Send>> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+impl Server {
+    /// Starts listening for clients in the background.
+    pub fn start(address: Address) -> io::Result<Self> {
+        let listener = Listener::bind(&address)?;
+
+        let (writer, events) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let _ = thread::spawn(move || write(&events));
+
+        let _ = thread::spawn({
+            let writer = writer.clone();
+            let stop = Arc::clone(&stop);
+
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok(client) => {
+                            if writer.send(Event::Client(client)).is_err() {
+                                return;
+                            }
+                        }
+                        Err(_) => thread::sleep(ACCEPT_INTERVAL),
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            address,
+            writer,
+            stop,
+        })
+    }
+
+    /// Queues the theme to be sent to every client, unless it was the last
+    /// one sent.
+    pub fn publish(&self, file: ThemeFile) {
+        let _ = self.writer.send(Event::Theme(Box::new(file)));
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        #[cfg(unix)]
+        if let Address::Unix(path) = &self.address {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Usage, Variant, all_themes, pair};
+    use iced::theme::palette::Pair;
+    use iced_theme_client::Connection;
+    use std::time::Instant;
+
+    #[test]
+    fn clients_decode_every_theme() {
+        for theme in all_themes() {
+            let extended = theme.extended_palette();
+            let file = ThemeFile::new(
+                theme.to_string(),
+                extended,
+                &[(Usage::Primary, Variant::Weak)],
+            );
+
+            let line = theme_file::to_line(&file).unwrap();
+            assert!(!line.contains('\n'));
+
+            let decoded = iced_theme_client::decode(&line).unwrap();
+            assert_eq!(decoded.to_string(), theme.to_string());
+
+            for usage in Usage::ALL {
+                for variant in Variant::ALL {
+                    // Colors are sent as hex, so 8 bits per channel
+                    let rgba8 = |pair: Pair| [pair.color.into_rgba8(), pair.text.into_rgba8()];
+
+                    assert_eq!(
+                        rgba8(pair(decoded.extended_palette(), usage, variant)),
+                        rgba8(pair(extended, usage, variant)),
+                        "{theme}: {} {}",
+                        usage.label(),
+                        variant.label(),
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stalled_clients_do_not_block_publishing() {
+        let path =
+            std::env::temp_dir().join(format!("iced_theme_viewer-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let address = Address::Unix(path.clone());
+        let server = Server::start(address.clone()).unwrap();
+
+        // Connected, but never reading
+        let _stalled = std::os::unix::net::UnixStream::connect(&path).unwrap();
+
+        let mut connection = Connection::connect(&address).unwrap();
+        thread::sleep(ACCEPT_INTERVAL * 3);
+
+        let started = Instant::now();
+
+        for theme in all_themes().iter().cycle().take(2000) {
+            server.publish(ThemeFile::new(
+                theme.to_string(),
+                theme.extended_palette(),
+                &[],
+            ));
+        }
+
+        assert!(started.elapsed() < WRITE_TIMEOUT);
+
+        let last = all_themes()[(2000 - 1) % all_themes().len()].to_string();
+        let received =
+            connection.find_map(|theme| theme.ok().filter(|theme| theme.to_string() == last));
+
+        assert!(received.is_some());
+    }
+}
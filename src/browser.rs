@@ -0,0 +1,544 @@
+//! Every theme side by side, sorted and filtered by what their colors
+//! measure, to find the ones fitting a purpose.
+use crate::{
+    Usage, Variant, color,
+    generate::MinContrast,
+    pair, picker,
+    search::{Candidate, ThemeId},
+};
+use iced::{
+    Element, Length,
+    alignment::Vertical,
+    theme::palette::Extended,
+    widget::{self, button},
+};
+use std::{cmp::Ordering, fmt};
+
+/// The measures listed beside each theme, and the width of their column.
+const COLUMNS: [(&str, f32); 4] = [
+    ("Lightness", 80.0),
+    ("Hue", 56.0),
+    ("Contrast", 72.0),
+    ("Colorfulness", 100.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sort {
+    #[default]
+    Name,
+    Lightness,
+    Dark,
+    Hue,
+    Contrast,
+    Colorfulness,
+}
+
+impl Sort {
+    pub const ALL: [Sort; 6] = [
+        Sort::Name,
+        Sort::Lightness,
+        Sort::Dark,
+        Sort::Hue,
+        Sort::Contrast,
+        Sort::Colorfulness,
+    ];
+
+    /// Orders two themes, the darkest, most contrasted or most colorful
+    /// first.
+    fn compare(self, a: &(Candidate, Metrics), b: &(Candidate, Metrics)) -> Ordering {
+        let ((a, a_metrics), (b, b_metrics)) = (a, b);
+
+        match self {
+            Sort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Sort::Lightness => a_metrics.lightness.total_cmp(&b_metrics.lightness),
+            Sort::Dark => b_metrics
+                .is_dark
+                .cmp(&a_metrics.is_dark)
+                .then(a_metrics.lightness.total_cmp(&b_metrics.lightness)),
+            // Grays have no hue, and come last
+            Sort::Hue => match (a_metrics.hue, b_metrics.hue) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => a.is_none().cmp(&b.is_none()),
+            },
+            Sort::Contrast => b_metrics.contrast.total_cmp(&a_metrics.contrast),
+            Sort::Colorfulness => b_metrics.colorfulness.total_cmp(&a_metrics.colorfulness),
+        }
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Sort::Name => "Name",
+            Sort::Lightness => "Background lightness",
+            Sort::Dark => "Dark first",
+            Sort::Hue => "Primary hue",
+            Sort::Contrast => "Average contrast",
+            Sort::Colorfulness => "Colorfulness",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Brightness {
+    #[default]
+    Any,
+    Dark,
+    Light,
+}
+
+impl Brightness {
+    pub const ALL: [Brightness; 3] = [Brightness::Any, Brightness::Dark, Brightness::Light];
+}
+
+impl fmt::Display for Brightness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Brightness::Any => "Dark and light",
+            Brightness::Dark => "Dark",
+            Brightness::Light => "Light",
+        })
+    }
+}
+
+/// The least contrast the text of every primary cell must have with its
+/// color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimaryContrast {
+    #[default]
+    Any,
+    AtLeast(MinContrast),
+}
+
+impl PrimaryContrast {
+    pub const ALL: [PrimaryContrast; 4] = [
+        PrimaryContrast::Any,
+        PrimaryContrast::AtLeast(MinContrast::Large),
+        PrimaryContrast::AtLeast(MinContrast::Aa),
+        PrimaryContrast::AtLeast(MinContrast::Aaa),
+    ];
+}
+
+impl fmt::Display for PrimaryContrast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimaryContrast::Any => write!(f, "Any primary"),
+            PrimaryContrast::AtLeast(min_contrast) => write!(f, "Primary {min_contrast}"),
+        }
+    }
+}
+
+/// What the colors of a palette measure.
+#[derive(Debug, Clone, Copy)]
+pub struct Metrics {
+    /// The OKLCH lightness of the background.
+    pub lightness: f32,
+    pub is_dark: bool,
+    /// The OKLCH hue of the primary color, unless it is a gray.
+    pub hue: Option<f32>,
+    /// The average contrast between the color and the text of every cell.
+    pub contrast: f32,
+    /// The lowest contrast between the color and the text of a primary cell.
+    pub primary_contrast: f32,
+    /// The average OKLCH chroma of every cell.
+    pub colorfulness: f32,
+}
+
+impl Metrics {
+    pub fn new(palette: &Extended) -> Self {
+        let cells = Usage::ALL
+            .into_iter()
+            .flat_map(|usage| Variant::ALL.map(|variant| (usage, pair(palette, usage, variant))));
+
+        let count = (Usage::ALL.len() * Variant::ALL.len()) as f32;
+        let mut contrast = 0.0;
+        let mut colorfulness = 0.0;
+        let mut primary_contrast = f32::INFINITY;
+
+        for (usage, pair) in cells {
+            let ratio = color::contrast(pair.color, pair.text);
+
+            contrast += ratio / count;
+            colorfulness += color::to_oklch(pair.color).chroma / count;
+
+            if usage == Usage::Primary {
+                primary_contrast = primary_contrast.min(ratio);
+            }
+        }
+
+        let primary = palette.primary.base.color;
+
+        Self {
+            lightness: color::to_oklch(palette.background.base.color).l,
+            is_dark: palette.is_dark,
+            hue: color::hue_name(primary)
+                .map(|_| color::to_oklch(primary).hue.into_positive_degrees()),
+            contrast,
+            primary_contrast,
+            colorfulness,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Browser {
+    sort: Sort,
+    reverse: bool,
+    brightness: Brightness,
+    primary_contrast: PrimaryContrast,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Sort(Sort),
+    Reverse(bool),
+    Brightness(Brightness),
+    PrimaryContrast(PrimaryContrast),
+    Open(ThemeId),
+}
+
+impl Browser {
+    /// Applies a [`Message`], returning the theme to open if one was picked.
+    pub fn update(&mut self, message: Message) -> Option<ThemeId> {
+        match message {
+            Message::Sort(sort) => self.sort = sort,
+            Message::Reverse(reverse) => self.reverse = reverse,
+            Message::Brightness(brightness) => self.brightness = brightness,
+            Message::PrimaryContrast(primary_contrast) => self.primary_contrast = primary_contrast,
+            Message::Open(id) => return Some(id),
+        }
+
+        None
+    }
+
+    fn matches(&self, metrics: &Metrics) -> bool {
+        let brightness = match self.brightness {
+            Brightness::Any => true,
+            Brightness::Dark => metrics.is_dark,
+            Brightness::Light => !metrics.is_dark,
+        };
+
+        let primary_contrast = match self.primary_contrast {
+            PrimaryContrast::Any => true,
+            PrimaryContrast::AtLeast(min_contrast) => {
+                metrics.primary_contrast >= min_contrast.ratio()
+            }
+        };
+
+        brightness && primary_contrast
+    }
+
+    /// The themes which pass the filters, in order.
+    pub fn themes(&self, candidates: Vec<Candidate>) -> Vec<(Candidate, Metrics)> {
+        let mut themes: Vec<_> = candidates
+            .into_iter()
+            .map(|candidate| {
+                let metrics = Metrics::new(&candidate.palette);
+                (candidate, metrics)
+            })
+            .filter(|(_, metrics)| self.matches(metrics))
+            .collect();
+
+        themes.sort_by(|a, b| self.sort.compare(a, b));
+
+        if self.reverse {
+            themes.reverse();
+        }
+
+        themes
+    }
+
+    /// Lists the themes, with their measures in columns when `wide`, or
+    /// below their names otherwise.
+    pub fn view(
+        &self,
+        candidates: Vec<Candidate>,
+        current: &ThemeId,
+        wide: bool,
+    ) -> Element<'_, Message> {
+        let total = candidates.len();
+        let themes = self.themes(candidates);
+
+        let controls = widget::row!(
+            widget::text("Sort by"),
+            widget::pick_list(Sort::ALL, Some(self.sort), Message::Sort),
+            widget::checkbox("Reverse", self.reverse).on_toggle(Message::Reverse),
+            widget::pick_list(Brightness::ALL, Some(self.brightness), Message::Brightness),
+            widget::pick_list(
+                PrimaryContrast::ALL,
+                Some(self.primary_contrast),
+                Message::PrimaryContrast
+            ),
+        )
+        .spacing(8.0)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let count = widget::text(format!("{} of {total} themes", themes.len()))
+            .style(widget::text::secondary);
+
+        let heading = wide.then(|| {
+            widget::row!(widget::text("Theme").width(Length::Fill))
+                .extend(COLUMNS.map(|(label, width)| widget::text(label).width(width).into()))
+                .spacing(12.0)
+                .padding([0, 8])
+        });
+
+        let rows = themes.into_iter().map(|(candidate, metrics)| {
+            let colors = widget::row(
+                [
+                    candidate.palette.background.base.color,
+                    candidate.palette.primary.base.color,
+                    candidate.palette.success.base.color,
+                    candidate.palette.danger.base.color,
+                ]
+                .map(|color| picker::swatch(color).into()),
+            )
+            .spacing(2.0);
+
+            let values = [
+                format!("{:.0}%", metrics.lightness * 100.0),
+                metrics
+                    .hue
+                    .map_or_else(|| "gray".to_owned(), |hue| format!("{hue:.0}°")),
+                format!("{:.1}:1", metrics.contrast),
+                format!("{:.3}", metrics.colorfulness),
+            ];
+
+            let name = widget::text(candidate.name);
+
+            let content: Element<'_, Message> = if wide {
+                widget::row!(colors, name.width(Length::Fill))
+                    .extend(
+                        values
+                            .into_iter()
+                            .zip(COLUMNS)
+                            .map(|(value, (_, width))| widget::text(value).width(width).into()),
+                    )
+                    .spacing(12.0)
+                    .align_y(Vertical::Center)
+                    .into()
+            } else {
+                widget::row!(
+                    colors,
+                    widget::column!(name, widget::text(values.join(" · ")).size(12)),
+                )
+                .spacing(12.0)
+                .align_y(Vertical::Center)
+                .into()
+            };
+
+            widget::button(content)
+                .on_press(Message::Open(candidate.id.clone()))
+                .width(Length::Fill)
+                .padding([4, 8])
+                .style(if candidate.id == *current {
+                    button::primary
+                } else {
+                    button::text
+                })
+                .into()
+        });
+
+        widget::column!(controls, count)
+            .push_maybe(heading)
+            .push(widget::column(rows).spacing(2.0))
+            .spacing(12.0)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_themes;
+    use iced::{
+        Color, Theme,
+        theme::{Palette, palette::Pair},
+    };
+
+    fn candidate(name: &str, palette: Extended) -> Candidate {
+        Candidate::new(
+            ThemeId::BuiltIn(name.to_owned()),
+            name.to_owned(),
+            &[],
+            palette,
+        )
+    }
+
+    fn candidates() -> Vec<Candidate> {
+        all_themes()
+            .into_iter()
+            .map(|theme| candidate(&theme.to_string(), *theme.extended_palette()))
+            .chain([candidate("gray", gray())])
+            .collect()
+    }
+
+    /// A palette without a single hue.
+    fn gray() -> Extended {
+        let gray = Color::from_rgb(0.5, 0.5, 0.5);
+
+        Extended::generate(Palette {
+            background: Color::WHITE,
+            text: Color::BLACK,
+            primary: gray,
+            success: gray,
+            danger: gray,
+        })
+    }
+
+    fn themes(browser: &Browser) -> Vec<(Candidate, Metrics)> {
+        browser.themes(candidates())
+    }
+
+    fn sorted(sort: Sort) -> Vec<(Candidate, Metrics)> {
+        themes(&Browser {
+            sort,
+            ..Browser::default()
+        })
+    }
+
+    #[test]
+    fn metrics() {
+        let light = Metrics::new(Theme::Light.extended_palette());
+
+        assert!(!light.is_dark);
+        assert!((light.lightness - 1.0).abs() < 1e-3);
+        assert!(light.hue.is_some());
+        assert!((1.0..=21.0).contains(&light.contrast));
+        assert!(light.colorfulness > 0.0);
+
+        let dark = Metrics::new(Theme::Dark.extended_palette());
+
+        assert!(dark.is_dark);
+        assert!(dark.lightness < light.lightness);
+
+        let gray = Metrics::new(&gray());
+
+        assert_eq!(gray.hue, None);
+        assert!(gray.colorfulness < 1e-3);
+    }
+
+    #[test]
+    fn primary_contrast_is_the_lowest() {
+        let palette = Theme::Dracula.extended_palette();
+        let metrics = Metrics::new(palette);
+
+        let lowest = Variant::ALL
+            .map(|variant| {
+                let Pair { color, text } = pair(palette, Usage::Primary, variant);
+                color::contrast(color, text)
+            })
+            .into_iter()
+            .fold(f32::INFINITY, f32::min);
+
+        assert_eq!(metrics.primary_contrast, lowest);
+    }
+
+    #[test]
+    fn sorts() {
+        let names: Vec<String> = sorted(Sort::Name)
+            .into_iter()
+            .map(|(candidate, _)| candidate.name.to_lowercase())
+            .collect();
+        assert!(names.is_sorted());
+
+        let lightness = sorted(Sort::Lightness);
+        assert!(lightness.is_sorted_by(|(_, a), (_, b)| a.lightness <= b.lightness));
+
+        // Dark themes first, each group from the darkest
+        let dark = sorted(Sort::Dark);
+        assert!(dark.is_sorted_by(|(_, a), (_, b)| {
+            a.is_dark && !b.is_dark || a.is_dark == b.is_dark && a.lightness <= b.lightness
+        }));
+        assert!(dark.first().unwrap().1.is_dark);
+        assert!(!dark.last().unwrap().1.is_dark);
+
+        // Grays come after every hue
+        let hue = sorted(Sort::Hue);
+        let grays = hue
+            .iter()
+            .position(|(_, metrics)| metrics.hue.is_none())
+            .unwrap();
+        assert!(
+            hue[grays..]
+                .iter()
+                .all(|(_, metrics)| metrics.hue.is_none())
+        );
+        assert!(hue[..grays].is_sorted_by(|(_, a), (_, b)| a.hue <= b.hue));
+
+        let contrast = sorted(Sort::Contrast);
+        assert!(contrast.is_sorted_by(|(_, a), (_, b)| a.contrast >= b.contrast));
+
+        let colorfulness = sorted(Sort::Colorfulness);
+        assert!(colorfulness.is_sorted_by(|(_, a), (_, b)| a.colorfulness >= b.colorfulness));
+        assert_eq!(colorfulness.last().unwrap().0.name, "gray");
+    }
+
+    #[test]
+    fn reverse() {
+        let mut forward = sorted(Sort::Contrast);
+        forward.reverse();
+
+        let reversed = themes(&Browser {
+            sort: Sort::Contrast,
+            reverse: true,
+            ..Browser::default()
+        });
+
+        assert_eq!(
+            reversed
+                .iter()
+                .map(|(candidate, _)| &candidate.id)
+                .collect::<Vec<_>>(),
+            forward
+                .iter()
+                .map(|(candidate, _)| &candidate.id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn brightness_filter() {
+        let all = candidates().len();
+        let filtered = |brightness| {
+            themes(&Browser {
+                brightness,
+                ..Browser::default()
+            })
+        };
+
+        let (dark, light) = (filtered(Brightness::Dark), filtered(Brightness::Light));
+
+        assert!(dark.iter().all(|(_, metrics)| metrics.is_dark));
+        assert!(light.iter().all(|(_, metrics)| !metrics.is_dark));
+        assert!(!dark.is_empty() && !light.is_empty());
+        assert_eq!(dark.len() + light.len(), all);
+        assert_eq!(filtered(Brightness::Any).len(), all);
+    }
+
+    #[test]
+    fn primary_contrast_filter() {
+        for min_contrast in [MinContrast::Large, MinContrast::Aa, MinContrast::Aaa] {
+            let browser = Browser {
+                primary_contrast: PrimaryContrast::AtLeast(min_contrast),
+                ..Browser::default()
+            };
+            let kept: Vec<ThemeId> = themes(&browser)
+                .into_iter()
+                .map(|(candidate, _)| candidate.id)
+                .collect();
+
+            for candidate in candidates() {
+                let metrics = Metrics::new(&candidate.palette);
+
+                assert_eq!(
+                    kept.contains(&candidate.id),
+                    metrics.primary_contrast >= min_contrast.ratio(),
+                    "{} {min_contrast}",
+                    candidate.name
+                );
+            }
+        }
+    }
+}
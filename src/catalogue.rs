@@ -0,0 +1,207 @@
+//! Every built-in theme written into a directory, so that the palettes of
+//! different iced versions can be compared.
+use crate::{
+    all_themes, export,
+    sheet::Sheet,
+    theme_file::{self, Error, Metadata, ThemeFile},
+};
+use iced::{Theme, theme::palette::Extended};
+use serde::Deserialize;
+use serde_json::json;
+use std::{fs, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The viewer's own theme files.
+    Json,
+    Export(export::Format),
+    Svg,
+}
+
+impl Format {
+    /// Every format, with the name it is given on the command line.
+    pub const ALL: [(&'static str, Format); 8] = [
+        ("json", Format::Json),
+        ("css", Format::Export(export::Format::Css)),
+        ("scss", Format::Export(export::Format::Scss)),
+        ("gpl", Format::Export(export::Format::Gpl)),
+        ("ase", Format::Export(export::Format::Ase)),
+        ("tokens", Format::Export(export::Format::Tokens)),
+        ("rust", Format::Export(export::Format::Rust)),
+        ("svg", Format::Svg),
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Format::ALL
+            .into_iter()
+            .find_map(|(format_name, format)| (format_name == name).then_some(format))
+    }
+
+    fn name(self) -> &'static str {
+        Format::ALL
+            .into_iter()
+            .find_map(|(name, format)| (format == self).then_some(name))
+            .unwrap_or_default()
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Export(format) => format.extension(),
+            Format::Svg => "svg",
+        }
+    }
+
+    fn contents(self, theme: &Theme) -> Result<Vec<u8>, Error> {
+        let name = theme.to_string();
+
+        match self {
+            Format::Json => {
+                let file = ThemeFile::new(name, theme.extended_palette(), &[]);
+                theme_file::to_json(&file).map(String::into_bytes)
+            }
+            Format::Export(format) => {
+                Ok(format.export(&Metadata::new(name), theme.extended_palette()))
+            }
+            Format::Svg => Ok(Sheet::new([theme]).to_svg().into_bytes()),
+        }
+    }
+}
+
+/// Writes every theme of [`all_themes`] into `directory`, along with an
+/// `index.json` listing them. Returns how many themes were written.
+pub fn write(directory: &Path, format: Format) -> Result<usize, Error> {
+    fs::create_dir_all(directory)?;
+
+    let themes = all_themes();
+    let mut index = Vec::with_capacity(themes.len());
+
+    for theme in &themes {
+        let name = theme.to_string();
+        let file = format!("{}.{}", export::slug(&name), format.extension());
+
+        fs::write(directory.join(&file), format.contents(theme)?)?;
+
+        index.push(json!({
+            "name": name,
+            "file": file,
+            "is_dark": theme.extended_palette().is_dark,
+        }));
+    }
+
+    let index = json!({
+        "format": format.name(),
+        "themes": index,
+    });
+
+    let mut contents =
+        serde_json::to_string_pretty(&index).map_err(|error| Error::Encode(error.to_string()))?;
+    contents.push('\n');
+
+    fs::write(directory.join("index.json"), contents)?;
+
+    Ok(themes.len())
+}
+
+/// Reads back the palettes of a catalogue written in the [`Format::Json`]
+/// format, with their names.
+pub fn read(directory: &Path) -> Result<Vec<(String, Extended)>, Error> {
+    let index = fs::read_to_string(directory.join("index.json"))?;
+    let index: Index =
+        serde_json::from_str(&index).map_err(|error| Error::Parse(error.to_string()))?;
+
+    if index.format != Format::Json.name() {
+        return Err(Error::Parse(format!(
+            "only json catalogues can be read, not {}",
+            index.format
+        )));
+    }
+
+    index
+        .themes
+        .into_iter()
+        .map(|entry| {
+            let contents = fs::read_to_string(directory.join(&entry.file))?;
+            let file = theme_file::parse(&contents)?;
+
+            Ok((entry.name, file.extended()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct Index {
+    format: String,
+    themes: Vec<Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    name: String,
+    file: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Usage, Variant, pair};
+    use std::path::PathBuf;
+
+    fn temporary(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("iced_theme_viewer-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let directory = temporary("catalogue");
+        let written = write(&directory, Format::Json);
+        let read = read(&directory);
+        let _ = fs::remove_dir_all(&directory);
+
+        let themes = all_themes();
+        let read = read.unwrap();
+
+        assert_eq!(written.unwrap(), themes.len());
+        assert_eq!(read.len(), themes.len());
+
+        for (theme, (name, extended)) in themes.iter().zip(&read) {
+            let original = theme.extended_palette();
+
+            assert_eq!(*name, theme.to_string());
+            assert_eq!(extended.is_dark, original.is_dark, "{name}");
+
+            // To the precision of the hex values the files hold
+            for usage in Usage::ALL {
+                for variant in Variant::ALL {
+                    let rgba8 = |extended| {
+                        let pair = pair(extended, usage, variant);
+                        (pair.color.into_rgba8(), pair.text.into_rgba8())
+                    };
+
+                    assert_eq!(
+                        rgba8(extended),
+                        rgba8(original),
+                        "{name} {usage:?} {variant:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_json_catalogues_are_read() {
+        let directory = temporary("catalogue-css");
+        let written = write(&directory, Format::Export(export::Format::Css));
+        let index = fs::read_to_string(directory.join("index.json"));
+        let read = read(&directory);
+        let _ = fs::remove_dir_all(&directory);
+
+        assert_eq!(written.unwrap(), all_themes().len());
+        assert!(
+            index
+                .unwrap()
+                .contains("\"file\": \"tokyo-night-storm.css\"")
+        );
+        assert!(matches!(read, Err(Error::Parse(_))));
+    }
+}
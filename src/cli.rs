@@ -0,0 +1,251 @@
+//! Running without a window, so that files can be generated by scripts and
+//! on CI machines.
+use crate::{
+    all_themes, catalogue,
+    color::to_hex,
+    custom_theme,
+    diff::{Comparison, Diff},
+    import,
+    sheet::Sheet,
+};
+use iced::Theme;
+use iced_theme_client::{Address, Connection};
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const USAGE: &str = "\
+Usage:
+    iced_theme_viewer [--fresh]
+        Opens the viewer, restoring the last session unless --fresh is
+        given. Favorite and recently viewed themes are kept either way.
+    iced_theme_viewer sheet (--all | THEME...) --output PATH
+        Renders a swatch sheet of the themes to an .svg or .png file.
+    iced_theme_viewer catalogue FORMAT DIRECTORY
+        Writes every built-in theme into the directory, along with an
+        index.json. FORMAT is one of json, css, scss, gpl, ase, tokens, rust
+        or svg.
+    iced_theme_viewer diff BEFORE AFTER [--json]
+        Lists the colors which differ between two themes, or between two
+        directories written by `catalogue json`, along with their ΔE.
+    iced_theme_viewer listen [ADDRESS]
+        Prints every theme broadcast by a viewer, 127.0.0.1:7878 by default.
+        Unix sockets are given as unix:PATH.
+
+THEME is either the name of a built-in theme, such as \"Tokyo Night\", or the
+path to a theme file.";
+
+#[derive(Debug)]
+pub enum Command {
+    View {
+        /// Whether to start with a new session.
+        fresh: bool,
+    },
+    Help,
+    Sheet {
+        themes: Vec<Theme>,
+        output: PathBuf,
+    },
+    Catalogue {
+        format: catalogue::Format,
+        directory: PathBuf,
+    },
+    Diff {
+        before: String,
+        after: String,
+        json: bool,
+    },
+    Listen {
+        address: Address,
+    },
+}
+
+impl Command {
+    /// Parses the command line arguments, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+
+        let Some(command) = args.next() else {
+            return Ok(Command::View { fresh: false });
+        };
+
+        match command.as_str() {
+            "--fresh" => match args.next() {
+                Some(arg) => Err(format!("Unexpected argument {arg:?}")),
+                None => Ok(Command::View { fresh: true }),
+            },
+            "-h" | "--help" | "help" => Ok(Command::Help),
+            "sheet" => {
+                let mut themes = Vec::new();
+                let mut output = None;
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--all" => themes.extend(all_themes()),
+                        "-o" | "--output" => {
+                            let path = args.next().ok_or("--output needs a path")?;
+                            output = Some(PathBuf::from(path));
+                        }
+                        _ => themes.push(theme(&arg)?),
+                    }
+                }
+
+                if themes.is_empty() {
+                    return Err("No themes given".to_owned());
+                }
+
+                let output = output.ok_or("No --output given")?;
+
+                Ok(Command::Sheet { themes, output })
+            }
+            "catalogue" => {
+                let format = args.next().ok_or("No format given")?;
+                let format = catalogue::Format::parse(&format)
+                    .ok_or_else(|| format!("Unknown format {format:?}"))?;
+                let directory = args.next().ok_or("No directory given")?;
+
+                Ok(Command::Catalogue {
+                    format,
+                    directory: PathBuf::from(directory),
+                })
+            }
+            "diff" => {
+                let mut paths = Vec::new();
+                let mut json = false;
+
+                for arg in args {
+                    match arg.as_str() {
+                        "--json" => json = true,
+                        _ => paths.push(arg),
+                    }
+                }
+
+                let [before, after] = <[String; 2]>::try_from(paths)
+                    .map_err(|_| "diff needs exactly two themes or directories")?;
+
+                Ok(Command::Diff {
+                    before,
+                    after,
+                    json,
+                })
+            }
+            "listen" => {
+                let address = args
+                    .next()
+                    .as_deref()
+                    .unwrap_or(iced_theme_client::DEFAULT_ADDRESS)
+                    .parse()
+                    .map_err(|error| format!("Invalid address: {error}"))?;
+
+                Ok(Command::Listen { address })
+            }
+            _ => Err(format!("Unknown command {command:?}")),
+        }
+    }
+
+    /// Runs a command other than [`Command::View`], which opens a window.
+    pub fn run(self) -> Result<(), String> {
+        match self {
+            Command::View { .. } => unreachable!("the viewer is opened by main"),
+            Command::Help => {
+                println!("{USAGE}");
+                Ok(())
+            }
+            Command::Sheet { themes, output } => {
+                let sheet = Sheet::new(&themes);
+
+                let contents = match output.extension().and_then(OsStr::to_str) {
+                    Some("svg") => sheet.to_svg().into_bytes(),
+                    Some("png") => sheet.to_png().map_err(|error| error.to_string())?,
+                    _ => return Err("The output must be an .svg or .png file".to_owned()),
+                };
+
+                write(&output, &contents)
+            }
+            Command::Catalogue { format, directory } => {
+                let count = catalogue::write(&directory, format).map_err(|error| {
+                    format!("Could not write to {}: {error}", directory.display())
+                })?;
+
+                println!("Wrote {count} themes to {}", directory.display());
+                Ok(())
+            }
+            Command::Diff {
+                before,
+                after,
+                json,
+            } => {
+                let (text, value) = if Path::new(&before).is_dir() {
+                    let read = |directory: &str| {
+                        catalogue::read(Path::new(directory))
+                            .map_err(|error| format!("Could not read {directory:?}: {error}"))
+                    };
+
+                    let comparison = Comparison::new(&read(&before)?, &read(&after)?);
+                    (comparison.to_string(), comparison.to_json())
+                } else {
+                    let diff = Diff::new(
+                        theme(&before)?.extended_palette(),
+                        theme(&after)?.extended_palette(),
+                    );
+                    (diff.to_string(), diff.to_json())
+                };
+
+                if json {
+                    println!("{value:#}");
+                } else {
+                    print!("{text}");
+                }
+
+                Ok(())
+            }
+            Command::Listen { address } => {
+                let connection = Connection::connect(&address)
+                    .map_err(|error| format!("Could not connect to {address}: {error}"))?;
+
+                for theme in connection {
+                    let theme = theme.map_err(|error| error.to_string())?;
+                    let palette = theme.palette();
+
+                    println!(
+                        "{theme}: background {}, text {}, primary {}, success {}, danger {}",
+                        to_hex(palette.background),
+                        to_hex(palette.text),
+                        to_hex(palette.primary),
+                        to_hex(palette.success),
+                        to_hex(palette.danger),
+                    );
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Finds a built-in theme by name, or loads a theme file.
+fn theme(name: &str) -> Result<Theme, String> {
+    if let Some(theme) = all_themes()
+        .into_iter()
+        .find(|theme| theme.to_string().eq_ignore_ascii_case(name))
+    {
+        return Ok(theme);
+    }
+
+    let path = PathBuf::from(name);
+    let contents =
+        fs::read_to_string(&path).map_err(|error| format!("Unknown theme {name:?}: {error}"))?;
+
+    let file = import::parse(&path, &contents)
+        .map_err(|error| format!("Could not open {name:?}: {error}"))?
+        .file;
+
+    Ok(custom_theme(file.metadata.name.clone(), file.extended()))
+}
+
+fn write(path: &PathBuf, contents: &[u8]) -> Result<(), String> {
+    fs::write(path, contents)
+        .map_err(|error| format!("Could not write {}: {error}", path.display()))
+}
@@ -0,0 +1,321 @@
+use iced::Color;
+use palette::{
+    FromColor, Hsl, Lab, Oklch, Srgb,
+    color_difference::{Ciede2000, Wcag21RelativeContrast},
+};
+use std::fmt;
+
+/// The WCAG 2.1 contrast ratio between two colors, from 1.0 to 21.0.
+pub fn contrast(a: Color, b: Color) -> f32 {
+    Srgb::from(a).relative_contrast(Srgb::from(b))
+}
+
+/// The CIEDE2000 difference between two colors, where about 1.0 is the
+/// smallest difference people notice.
+pub fn delta_e(a: Color, b: Color) -> f32 {
+    let lab = |color: Color| Lab::from_color(Srgb::from(color));
+
+    lab(a).difference(lab(b))
+}
+
+pub fn to_oklch(color: Color) -> Oklch {
+    Oklch::from_color(Srgb::from(color))
+}
+
+/// Converts from OKLCH, clamping the result into the sRGB gamut.
+pub fn from_oklch(lightness: f32, chroma: f32, hue: f32) -> Color {
+    Srgb::from_color(Oklch::new(lightness, chroma, hue)).into()
+}
+
+/// The shortest angle between the OKLCH hues of two colors, in degrees.
+pub fn hue_distance(a: Color, b: Color) -> f32 {
+    let a = to_oklch(a).hue.into_positive_degrees();
+    let b = to_oklch(b).hue.into_positive_degrees();
+
+    let distance = (a - b).abs();
+    distance.min(360.0 - distance)
+}
+
+/// The name of a color's hue, or `None` for colors too close to gray to
+/// have one.
+pub fn hue_name(color: Color) -> Option<&'static str> {
+    let oklch = to_oklch(color);
+
+    if oklch.chroma < 0.03 {
+        return None;
+    }
+
+    Some(match oklch.hue.into_positive_degrees() {
+        hue if hue < 15.0 => "pink",
+        hue if hue < 45.0 => "red",
+        hue if hue < 80.0 => "orange",
+        hue if hue < 120.0 => "yellow",
+        hue if hue < 175.0 => "green",
+        hue if hue < 225.0 => "cyan",
+        hue if hue < 285.0 => "blue",
+        hue if hue < 330.0 => "purple",
+        _ => "pink",
+    })
+}
+
+/// Formats a color as `#rrggbb`, or `#rrggbbaa` when it is translucent.
+pub fn to_hex(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+
+    if a == u8::MAX {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+/// A textual representation colors can be copied in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Hex,
+    Rgb,
+    Hsl,
+    ColorMacro,
+    FromRgb,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Format::Hex,
+        Format::Rgb,
+        Format::Hsl,
+        Format::ColorMacro,
+        Format::FromRgb,
+    ];
+
+    pub fn format(self, color: Color) -> String {
+        let opaque = color.a >= 1.0;
+        let [r, g, b, _] = color.into_rgba8();
+
+        match self {
+            Format::Hex => to_hex(color),
+            Format::Rgb if opaque => format!("rgb({r}, {g}, {b})"),
+            Format::Rgb => format!("rgba({r}, {g}, {b}, {:.2})", color.a),
+            Format::Hsl => {
+                let hsl = Hsl::from_color(Srgb::from(color));
+                let (h, s, l) = (
+                    hsl.hue.into_positive_degrees(),
+                    hsl.saturation * 100.0,
+                    hsl.lightness * 100.0,
+                );
+
+                if opaque {
+                    format!("hsl({h:.1}, {s:.1}%, {l:.1}%)")
+                } else {
+                    format!("hsla({h:.1}, {s:.1}%, {l:.1}%, {:.2})", color.a)
+                }
+            }
+            Format::ColorMacro if opaque => format!("color!(0x{r:02x}{g:02x}{b:02x})"),
+            Format::ColorMacro => format!("color!(0x{r:02x}{g:02x}{b:02x}, {:.2})", color.a),
+            Format::FromRgb if opaque => format!(
+                "Color::from_rgb({:.3}, {:.3}, {:.3})",
+                color.r, color.g, color.b
+            ),
+            Format::FromRgb => format!(
+                "Color::from_rgba({:.3}, {:.3}, {:.3}, {:.3})",
+                color.r, color.g, color.b, color.a
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Hex => write!(f, "Hex"),
+            Format::Rgb => write!(f, "rgb()"),
+            Format::Hsl => write!(f, "hsl()"),
+            Format::ColorMacro => write!(f, "color!"),
+            Format::FromRgb => write!(f, "Color::from_rgb"),
+        }
+    }
+}
+
+/// Parses a color in any of the [`Format`]s, as well as bare hex values
+/// and comma separated channels.
+pub fn parse(input: &str) -> Option<Color> {
+    let input = input.trim();
+
+    if input.is_empty() || !input.is_ascii() {
+        return None;
+    }
+
+    let (function, arguments) = match input
+        .strip_suffix(')')
+        .and_then(|rest| rest.split_once('('))
+    {
+        Some((function, arguments)) => (function.trim(), arguments),
+        None if input.contains(',') => ("rgb", input),
+        None => return Color::parse(input),
+    };
+
+    let arguments: Vec<&str> = arguments.split(',').map(str::trim).collect();
+
+    let alpha = |index: usize| match arguments.get(index) {
+        Some(alpha) => alpha.parse::<f32>().ok().filter(is_unit),
+        None => Some(1.0),
+    };
+
+    let bytes = || {
+        arguments
+            .iter()
+            .take(3)
+            .map(|channel| match channel.strip_prefix("0x") {
+                Some(hex) => u8::from_str_radix(hex, 16).ok(),
+                None => channel.parse::<u8>().ok(),
+            })
+            .collect::<Option<Vec<u8>>>()
+            .filter(|channels| channels.len() == 3)
+    };
+
+    match function {
+        "rgb" | "rgba" | "Color::from_rgb8" | "Color::from_rgba8" if arguments.len() <= 4 => {
+            let channels = bytes()?;
+            Some(Color::from_rgba8(
+                channels[0],
+                channels[1],
+                channels[2],
+                alpha(3)?,
+            ))
+        }
+        "color!" if arguments.len() <= 2 => {
+            let hex = u32::from_str_radix(arguments[0].trim_start_matches("0x"), 16)
+                .ok()
+                .filter(|hex| *hex <= 0xff_ffff)?;
+            let [_, r, g, b] = hex.to_be_bytes();
+
+            Some(Color::from_rgba8(r, g, b, alpha(1)?))
+        }
+        "color!" if arguments.len() <= 4 => {
+            let channels = bytes()?;
+            Some(Color::from_rgba8(
+                channels[0],
+                channels[1],
+                channels[2],
+                alpha(3)?,
+            ))
+        }
+        "hsl" | "hsla" if (3..=4).contains(&arguments.len()) => {
+            let percent = |value: &str| {
+                value
+                    .trim_end_matches('%')
+                    .parse::<f32>()
+                    .ok()
+                    .map(|percent| percent / 100.0)
+                    .filter(is_unit)
+            };
+
+            let hsl = Hsl::new(
+                arguments[0]
+                    .trim_end_matches("deg")
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|hue| hue.is_finite())?,
+                percent(arguments[1])?,
+                percent(arguments[2])?,
+            );
+
+            let rgb: Color = Srgb::from_color(hsl).into();
+            Some(Color {
+                a: alpha(3)?,
+                ..rgb
+            })
+        }
+        "Color::from_rgb" | "Color::from_rgba" if (3..=4).contains(&arguments.len()) => {
+            let channels = arguments
+                .iter()
+                .map(|channel| {
+                    channel
+                        .trim_end_matches("f32")
+                        .trim_end_matches('_')
+                        .parse::<f32>()
+                        .ok()
+                        .filter(is_unit)
+                })
+                .collect::<Option<Vec<f32>>>()?;
+
+            Some(Color::from_rgba(
+                channels[0],
+                channels[1],
+                channels[2],
+                channels.get(3).copied().unwrap_or(1.0),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Whether a value is within 0 to 1, as color channels and opacity are.
+/// NaN is not.
+fn is_unit(value: &f32) -> bool {
+    (0.0..=1.0).contains(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_format() {
+        let color = Color::from_rgb8(0x12, 0x34, 0x56);
+        let translucent = Color::from_rgba8(0x12, 0x34, 0x56, 0.5);
+
+        for format in Format::ALL {
+            let parsed = parse(&format.format(color)).unwrap();
+            assert_eq!(parsed.into_rgba8(), color.into_rgba8(), "{format}");
+
+            let parsed = parse(&format.format(translucent)).unwrap();
+            assert_eq!(parsed.into_rgba8(), translucent.into_rgba8(), "{format}");
+        }
+
+        assert_eq!(parse("#123456"), Some(color));
+        assert_eq!(parse(" 123456 "), Some(color));
+        assert_eq!(parse("18, 52, 86"), Some(color));
+        assert_eq!(parse("color!(18, 52, 86)"), Some(color));
+        assert_eq!(parse("color!(0x12, 0x34, 0x56)"), Some(color));
+        assert_eq!(parse("color!(0x12, 0x34, 0x56, 0.5)"), Some(translucent));
+        assert_eq!(parse("Color::from_rgb8(18, 52, 86)"), Some(color));
+        assert_eq!(parse("color!(0x123456, 0.5)"), Some(translucent));
+        assert_eq!(parse("rgba(18, 52, 86, 0.5)"), Some(translucent));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for input in [
+            "",
+            "aéée",
+            "#12345g",
+            "rgb(1, 2)",
+            "rgb(1, 2, 256)",
+            "rgb(1, 2, 3, 4, 5)",
+            "rgba(1, 2, 3, 5)",
+            "rgba(1, 2, 3, -0.5)",
+            "rgba(1, 2, 3, NaN)",
+            "hsla(120, 50%, 50%, inf)",
+            "color!(0x123456, 2)",
+            "color!(0x1123456)",
+            "color!(0xff123456)",
+            "Color::from_rgba(0.1, 0.2, 0.3, 1.5)",
+            "Color::from_rgba(0.1, 0.2, 0.3, NaN)",
+            "Color::from_rgb(2.0, -1, 0.5)",
+            "Color::from_rgb(NaN, 0.2, 0.3)",
+            "Color::from_rgb(0.1, inf, 0.3)",
+            "hsl(NaN, 50%, 50%)",
+            "hsl(inf, 50%, 50%)",
+            "hsl(0, 150%, 50%)",
+            "hsl(0, 50%, -10%)",
+            "hsl(0, NaN%, 50%)",
+            "color!(0x12, 0x34, 0x567)",
+            "color!(0x12, 0x34, 0xzz)",
+            "cmyk(1, 2, 3, 4)",
+        ] {
+            assert_eq!(parse(input), None, "{input:?}");
+        }
+    }
+}
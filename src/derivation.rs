@@ -0,0 +1,313 @@
+//! How iced derives the extended palette of a theme from the five colors of
+//! its [`Palette`], reimplemented to explain where each cell comes from.
+use crate::{Usage, Variant, color};
+use iced::{
+    Color,
+    theme::{Palette, palette::Pair},
+};
+use palette::{FromColor, Hsl, Mix, Srgb};
+use std::fmt;
+
+/// The contrast the palette's text needs with a cell to be kept.
+const READABLE: f32 = 7.0;
+/// The HSL lightness under which a color is dark.
+const DARK: f32 = 0.6;
+
+/// A color of the palette a cell is derived from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Background,
+    Text,
+    Primary,
+    Success,
+    Danger,
+    /// The base secondary color, itself mixed from the background and text.
+    SecondaryBase,
+}
+
+impl Source {
+    fn color(self, palette: &Palette) -> Color {
+        match self {
+            Source::Background => palette.background,
+            Source::Text => palette.text,
+            Source::Primary => palette.primary,
+            Source::Success => palette.success,
+            Source::Danger => palette.danger,
+            Source::SecondaryBase => mix(palette.background, palette.text, 0.2),
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Source::Background => "background",
+            Source::Text => "text",
+            Source::Primary => "primary",
+            Source::Success => "success",
+            Source::Danger => "danger",
+            Source::SecondaryBase => "secondary base",
+        })
+    }
+}
+
+/// How the color of a cell is made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    /// Taken from the palette as it is.
+    Palette(Source),
+    /// The first color moved a fraction of the way to the second, in linear
+    /// sRGB.
+    Mix(Source, Source, f32),
+    /// Lightened when its HSL lightness is under 0.6, and darkened otherwise.
+    Deviate {
+        source: Source,
+        amount: f32,
+        lightness: f32,
+    },
+}
+
+impl Step {
+    fn color(self, palette: &Palette) -> Color {
+        match self {
+            Step::Palette(source) => source.color(palette),
+            Step::Mix(from, to, factor) => mix(from.color(palette), to.color(palette), factor),
+            Step::Deviate { source, amount, .. } => deviate(source.color(palette), amount),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Step::Palette(source) => write!(f, "The palette's {source} color"),
+            Step::Mix(from, to, factor) => write!(
+                f,
+                "Mixes {from} with {:.0}% of {to}, in linear sRGB",
+                factor * 100.0
+            ),
+            Step::Deviate {
+                source,
+                amount,
+                lightness,
+            } if lightness < DARK => write!(
+                f,
+                "Lightens {source} by {amount:.2} of HSL lightness, since at {lightness:.2} it is \
+                 darker than {DARK}"
+            ),
+            Step::Deviate {
+                source,
+                amount,
+                lightness,
+            } => write!(
+                f,
+                "Darkens {source} by {amount:.2} of HSL lightness, since at {lightness:.2} it is not \
+                 darker than {DARK}"
+            ),
+        }
+    }
+}
+
+/// Why the text of a cell is the color it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Readability {
+    /// The palette's text contrasts enough with the cell.
+    Kept { contrast: f32 },
+    /// The palette's text contrasts too little, and was replaced with white
+    /// or black, whichever contrasts more.
+    Replaced {
+        contrast: f32,
+        white: f32,
+        black: f32,
+    },
+}
+
+impl fmt::Display for Readability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Readability::Kept { contrast } => write!(
+                f,
+                "The palette's text, since its {contrast:.1}:1 contrast reaches {READABLE}:1"
+            ),
+            Readability::Replaced {
+                contrast,
+                white,
+                black,
+            } => {
+                let (chosen, other) = if white >= black {
+                    ("White", "black")
+                } else {
+                    ("Black", "white")
+                };
+
+                write!(
+                    f,
+                    "{chosen} text, since the palette's only has {contrast:.1}:1 contrast of the \
+                     {READABLE}:1 needed, and {} has {:.1}:1 against {other}'s {:.1}:1",
+                    chosen.to_lowercase(),
+                    white.max(black),
+                    white.min(black),
+                )
+            }
+        }
+    }
+}
+
+/// The cell iced generates from a palette, and how.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Derivation {
+    pub step: Step,
+    pub readability: Readability,
+    pub pair: Pair,
+}
+
+/// Derives a cell the way [`iced::theme::palette::Extended::generate`] does.
+pub fn derive(palette: &Palette, usage: Usage, variant: Variant) -> Derivation {
+    // The color of the rows which are generated from one of their own
+    let base = match usage {
+        Usage::Primary => Source::Primary,
+        Usage::Success => Source::Success,
+        Usage::Danger => Source::Danger,
+        Usage::Background | Usage::Secondary => Source::Background,
+    };
+
+    let step = match (usage, variant) {
+        (Usage::Background, Variant::Base) => Step::Palette(Source::Background),
+        (Usage::Background, Variant::Weak) => Step::Mix(Source::Background, Source::Text, 0.15),
+        (Usage::Background, Variant::Strong) => Step::Mix(Source::Background, Source::Text, 0.40),
+        (Usage::Secondary, Variant::Base) => Step::Mix(Source::Background, Source::Text, 0.2),
+        (Usage::Secondary, Variant::Weak) => Step::Mix(Source::SecondaryBase, Source::Text, 0.1),
+        (Usage::Secondary, Variant::Strong) => Step::Mix(Source::SecondaryBase, Source::Text, 0.3),
+        (_, Variant::Base) => Step::Palette(base),
+        (_, Variant::Weak) => Step::Mix(base, Source::Background, 0.4),
+        (_, Variant::Strong) => Step::Deviate {
+            source: base,
+            amount: 0.1,
+            lightness: lightness(base.color(palette)),
+        },
+    };
+
+    let color = step.color(palette);
+    let contrast = color::contrast(color, palette.text);
+
+    let (readability, text) = if contrast >= READABLE {
+        (Readability::Kept { contrast }, palette.text)
+    } else {
+        let white = color::contrast(color, Color::WHITE);
+        let black = color::contrast(color, Color::BLACK);
+
+        let text = if white >= black {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        };
+
+        (
+            Readability::Replaced {
+                contrast,
+                white,
+                black,
+            },
+            text,
+        )
+    };
+
+    Derivation {
+        step,
+        readability,
+        pair: Pair { color, text },
+    }
+}
+
+fn mix(a: Color, b: Color, factor: f32) -> Color {
+    let a = Srgb::from(a).into_linear();
+    let b = Srgb::from(b).into_linear();
+
+    Srgb::from_linear(a.mix(b, factor)).into()
+}
+
+fn deviate(color: Color, amount: f32) -> Color {
+    let mut hsl = Hsl::from_color(Srgb::from(color));
+
+    hsl.lightness = if hsl.lightness < DARK {
+        (hsl.lightness + amount).min(1.0)
+    } else {
+        (hsl.lightness - amount).max(0.0)
+    };
+
+    Srgb::from_color(hsl).into()
+}
+
+fn lightness(color: Color) -> f32 {
+    Hsl::from_color(Srgb::from(color)).lightness
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{all_themes, pair};
+    use iced::{color, theme::palette::Extended};
+
+    fn assert_derives(palette: &Palette, extended: &Extended, name: &str) {
+        for usage in Usage::ALL {
+            for variant in Variant::ALL {
+                assert_eq!(
+                    derive(palette, usage, variant).pair,
+                    pair(extended, usage, variant),
+                    "{name}: {} {}",
+                    usage.label(),
+                    variant.label(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn built_in_themes() {
+        for theme in all_themes() {
+            assert_derives(
+                &theme.palette(),
+                theme.extended_palette(),
+                &theme.to_string(),
+            );
+        }
+    }
+
+    #[test]
+    fn unreadable_text() {
+        let palette = Palette {
+            background: color!(0x303030),
+            text: color!(0x707070),
+            primary: color!(0xe0c020),
+            success: color!(0x208040),
+            danger: color!(0xf04060),
+        };
+
+        assert_derives(&palette, &Extended::generate(palette), "gray on gray");
+
+        let derivation = derive(&palette, Usage::Primary, Variant::Base);
+
+        assert!(matches!(
+            derivation.readability,
+            Readability::Replaced { white, black, .. } if black > white
+        ));
+        assert_eq!(derivation.pair.text, Color::BLACK);
+    }
+
+    #[test]
+    fn deviates_both_ways() {
+        let palette = Palette {
+            primary: color!(0x202080),
+            success: color!(0xa0f0a0),
+            ..Palette::DARK
+        };
+
+        assert_derives(&palette, &Extended::generate(palette), "deviations");
+
+        let lightened = derive(&palette, Usage::Primary, Variant::Strong);
+        let darkened = derive(&palette, Usage::Success, Variant::Strong);
+
+        assert!(lightness(lightened.pair.color) > lightness(palette.primary));
+        assert!(lightness(darkened.pair.color) < lightness(palette.success));
+    }
+}
@@ -0,0 +1,322 @@
+//! Differences between two palettes, to catch colors which changed without
+//! anyone noticing.
+use crate::{Usage, Variant, color, pair};
+use iced::theme::palette::{Extended, Pair};
+use serde_json::{Value, json};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// Whether the palette is dark, before and after, if that changed.
+    pub is_dark: Option<(bool, bool)>,
+    pub changes: Vec<Change>,
+}
+
+/// A pair whose color or text color changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub usage: Usage,
+    pub variant: Variant,
+    pub before: Pair,
+    pub after: Pair,
+}
+
+impl Change {
+    /// The difference between the colors, see [`color::delta_e`].
+    pub fn delta_e(&self) -> f32 {
+        color::delta_e(self.before.color, self.after.color)
+    }
+
+    pub fn text_delta_e(&self) -> f32 {
+        color::delta_e(self.before.text, self.after.text)
+    }
+
+    pub fn to_json(self) -> Value {
+        json!({
+            "usage": self.usage,
+            "variant": self.variant,
+            "before": {
+                "color": color::to_hex(self.before.color),
+                "text": color::to_hex(self.before.text),
+            },
+            "after": {
+                "color": color::to_hex(self.after.color),
+                "text": color::to_hex(self.after.text),
+            },
+            "delta_e": self.delta_e(),
+            "text_delta_e": self.text_delta_e(),
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |pair: Pair| {
+            format!(
+                "{} / {}",
+                color::to_hex(pair.color),
+                color::to_hex(pair.text)
+            )
+        };
+
+        write!(
+            f,
+            "{} {}: {} → {} (ΔE {:.2}, text ΔE {:.2})",
+            self.usage.label(),
+            self.variant.label(),
+            hex(self.before),
+            hex(self.after),
+            self.delta_e(),
+            self.text_delta_e(),
+        )
+    }
+}
+
+impl Diff {
+    pub fn new(before: &Extended, after: &Extended) -> Self {
+        // Colors are compared as they are written to files, so that a round
+        // trip through one does not count as a change
+        let same = |a: Pair, b: Pair| {
+            color::to_hex(a.color) == color::to_hex(b.color)
+                && color::to_hex(a.text) == color::to_hex(b.text)
+        };
+
+        let changes = Usage::ALL
+            .into_iter()
+            .flat_map(|usage| Variant::ALL.map(|variant| (usage, variant)))
+            .filter_map(|(usage, variant)| {
+                let change = Change {
+                    usage,
+                    variant,
+                    before: pair(before, usage, variant),
+                    after: pair(after, usage, variant),
+                };
+
+                (!same(change.before, change.after)).then_some(change)
+            })
+            .collect();
+
+        Self {
+            is_dark: (before.is_dark != after.is_dark).then_some((before.is_dark, after.is_dark)),
+            changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_dark.is_none() && self.changes.is_empty()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "is_dark": self.is_dark.map(|(before, after)| json!({ "before": before, "after": after })),
+            "changes": self.changes.iter().map(|change| change.to_json()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        if let Some((before, after)) = self.is_dark {
+            writeln!(f, "Dark: {before} → {after}")?;
+        }
+
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The differences between two sets of named palettes, such as two
+/// catalogues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub changed: Vec<(String, Diff)>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Comparison {
+    pub fn new(before: &[(String, Extended)], after: &[(String, Extended)]) -> Self {
+        let find = |palettes: &[(String, Extended)], name: &str| {
+            palettes
+                .iter()
+                .find_map(|(other, extended)| (other == name).then_some(*extended))
+        };
+
+        let changed = after
+            .iter()
+            .filter_map(|(name, extended)| {
+                let diff = Diff::new(&find(before, name)?, extended);
+                (!diff.is_empty()).then(|| (name.clone(), diff))
+            })
+            .collect();
+
+        let missing = |from: &[(String, Extended)], to: &[(String, Extended)]| {
+            from.iter()
+                .filter(|(name, _)| find(to, name).is_none())
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+
+        Self {
+            changed,
+            added: missing(after, before),
+            removed: missing(before, after),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let changed: serde_json::Map<String, Value> = self
+            .changed
+            .iter()
+            .map(|(name, diff)| (name.clone(), diff.to_json()))
+            .collect();
+
+        json!({
+            "changed": changed,
+            "added": self.added,
+            "removed": self.removed,
+        })
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changed.is_empty() && self.added.is_empty() && self.removed.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        for (name, diff) in &self.changed {
+            writeln!(f, "{name}")?;
+
+            for line in diff.to_string().lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        for (label, names) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if !names.is_empty() {
+                writeln!(f, "{label}: {}", names.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        theme_file::{self, ThemeFile},
+        updated_extended,
+    };
+    use iced::{Color, Theme};
+
+    fn nord() -> Extended {
+        *Theme::Nord.extended_palette()
+    }
+
+    #[test]
+    fn identical_palettes() {
+        let diff = Diff::new(&nord(), &nord());
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes\n");
+        assert_eq!(diff.to_json(), json!({ "is_dark": null, "changes": [] }));
+    }
+
+    #[test]
+    fn one_changed_cell() {
+        let before = nord();
+        let pair = Pair {
+            color: Color::from_rgb8(0xff, 0x00, 0x00),
+            ..before.primary.weak
+        };
+        let after = updated_extended(before, pair, Usage::Primary, Variant::Weak);
+
+        let diff = Diff::new(&before, &after);
+
+        assert_eq!(diff.is_dark, None);
+        assert_eq!(
+            diff.changes,
+            vec![Change {
+                usage: Usage::Primary,
+                variant: Variant::Weak,
+                before: before.primary.weak,
+                after: pair,
+            }]
+        );
+        assert!(diff.changes[0].delta_e() > 1.0);
+        assert_eq!(diff.changes[0].text_delta_e(), 0.0);
+        assert!(diff.to_string().starts_with("Primary Weak: #"));
+        assert_eq!(diff.to_json()["changes"][0]["after"]["color"], "#ff0000");
+    }
+
+    #[test]
+    fn dark_only() {
+        let after = Extended {
+            is_dark: false,
+            ..nord()
+        };
+
+        let diff = Diff::new(&nord(), &after);
+
+        assert!(!diff.is_empty());
+        assert!(diff.changes.is_empty());
+        assert_eq!(diff.to_string(), "Dark: true → false\n");
+    }
+
+    #[test]
+    fn metadata_only_changes() {
+        let before = ThemeFile::new("Nord".to_owned(), &nord(), &[]);
+
+        // Renamed and described, then saved and read back, which rounds
+        // the colors to their hex values
+        let mut after = before.clone();
+        after.metadata.name = "My Nord".to_owned();
+        after.metadata.author = "Ada".to_owned();
+        after.metadata.tags = vec!["cold".to_owned()];
+        let after = theme_file::parse(&theme_file::to_json(&after).unwrap()).unwrap();
+
+        assert!(Diff::new(&before.extended(), &after.extended()).is_empty());
+    }
+
+    #[test]
+    fn comparisons() {
+        let before = [
+            ("Nord".to_owned(), nord()),
+            ("Light".to_owned(), *Theme::Light.extended_palette()),
+        ];
+        let after = [
+            (
+                "Nord".to_owned(),
+                Extended {
+                    is_dark: false,
+                    ..nord()
+                },
+            ),
+            ("Dark".to_owned(), *Theme::Dark.extended_palette()),
+        ];
+
+        let comparison = Comparison::new(&before, &after);
+
+        assert_eq!(
+            comparison
+                .changed
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["Nord"]
+        );
+        assert_eq!(comparison.added, ["Dark"]);
+        assert_eq!(comparison.removed, ["Light"]);
+        assert!(Comparison::new(&before, &before).changed.is_empty());
+    }
+}
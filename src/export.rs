@@ -0,0 +1,525 @@
+//! Writing palettes in formats other tools understand.
+use crate::{
+    Usage, Variant,
+    color::{self, to_hex},
+    pair,
+    theme_file::{Error, Metadata},
+};
+use iced::{Color, theme::palette::Extended};
+use serde_json::{Map, Value, json};
+use std::{fmt, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Css,
+    Scss,
+    Gpl,
+    Ase,
+    Tokens,
+    Rust,
+}
+
+impl Format {
+    pub const ALL: [Format; 6] = [
+        Format::Css,
+        Format::Scss,
+        Format::Gpl,
+        Format::Ase,
+        Format::Tokens,
+        Format::Rust,
+    ];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Css => "css",
+            Format::Scss => "scss",
+            Format::Gpl => "gpl",
+            Format::Ase => "ase",
+            Format::Tokens => "tokens.json",
+            Format::Rust => "rs",
+        }
+    }
+
+    /// Writes every pair of the palette, describing it with `metadata`
+    /// where the format allows it.
+    pub fn export(self, metadata: &Metadata, extended: &Extended) -> Vec<u8> {
+        match self {
+            Format::Css => {
+                let header: String = header(metadata)
+                    .map(|line| format!("/* {line} */\n"))
+                    .collect();
+                let properties: String = variables(extended)
+                    .map(|(variable, value)| format!("  --{variable}: {value};\n"))
+                    .collect();
+
+                format!("{header}:root {{\n{properties}}}\n").into_bytes()
+            }
+            Format::Scss => {
+                let header: String = header(metadata)
+                    .map(|line| format!("// {line}\n"))
+                    .collect();
+                let variables: String = variables(extended)
+                    .map(|(variable, value)| format!("${variable}: {value};\n"))
+                    .collect();
+
+                format!("{header}{variables}").into_bytes()
+            }
+            Format::Gpl => gpl(metadata, extended).into_bytes(),
+            Format::Ase => ase(extended),
+            Format::Tokens => tokens(metadata, extended).into_bytes(),
+            Format::Rust => rust(metadata, extended).into_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Css => write!(f, "CSS"),
+            Format::Scss => write!(f, "SCSS"),
+            Format::Gpl => write!(f, "GIMP palette"),
+            Format::Ase => write!(f, "Adobe swatches"),
+            Format::Tokens => write!(f, "Design tokens"),
+            Format::Rust => write!(f, "Rust"),
+        }
+    }
+}
+
+/// The lines describing a theme in the comments of a file: its name, then
+/// whichever of its author, description and tags it has.
+fn header(metadata: &Metadata) -> impl Iterator<Item = String> {
+    let Metadata {
+        name,
+        author,
+        description,
+        tags,
+    } = metadata;
+
+    [
+        Some(name.clone()),
+        (!author.is_empty()).then(|| format!("By {author}")),
+        (!description.is_empty()).then(|| description.clone()),
+        (!tags.is_empty()).then(|| format!("Tags: {}", tags.join(", "))),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// The variable names and values of every pair, such as
+/// `iced-primary-base` and `iced-primary-base-text`.
+fn variables(extended: &Extended) -> impl Iterator<Item = (String, String)> + '_ {
+    Usage::ALL.into_iter().flat_map(move |usage| {
+        Variant::ALL.into_iter().flat_map(move |variant| {
+            let pair = pair(extended, usage, variant);
+            let prefix = format!(
+                "iced-{}-{}",
+                usage.label().to_lowercase(),
+                variant.label().to_lowercase()
+            );
+
+            [
+                (prefix.clone(), to_hex(pair.color)),
+                (format!("{prefix}-text"), to_hex(pair.text)),
+            ]
+        })
+    })
+}
+
+/// Every color of the palette as a named swatch, such as `Primary Base`
+/// and `Primary Base Text`, grouped by usage.
+fn swatches(extended: &Extended) -> impl Iterator<Item = (Usage, Vec<(String, Color)>)> + '_ {
+    Usage::ALL.into_iter().map(move |usage| {
+        let swatches = Variant::ALL
+            .into_iter()
+            .flat_map(|variant| {
+                let pair = pair(extended, usage, variant);
+                let name = format!("{} {}", usage.label(), variant.label());
+
+                [
+                    (name.clone(), pair.color),
+                    (format!("{name} Text"), pair.text),
+                ]
+            })
+            .collect();
+
+        (usage, swatches)
+    })
+}
+
+/// A GIMP palette, which Inkscape and Krita read as well.
+fn gpl(metadata: &Metadata, extended: &Extended) -> String {
+    let colors: String = swatches(extended)
+        .flat_map(|(_, swatches)| swatches)
+        .map(|(swatch, color)| {
+            let [r, g, b, _] = color.into_rgba8();
+            format!("{r:3} {g:3} {b:3}\t{swatch}\n")
+        })
+        .collect();
+
+    let comments: String = header(metadata)
+        .skip(1)
+        .map(|line| format!("# {line}\n"))
+        .collect();
+
+    format!(
+        "GIMP Palette\nName: {}\nColumns: 6\n#\n{comments}{colors}",
+        metadata.name
+    )
+}
+
+/// An Adobe Swatch Exchange file, with a group of swatches per usage.
+fn ase(extended: &Extended) -> Vec<u8> {
+    const GROUP_START: u16 = 0xc001;
+    const GROUP_END: u16 = 0xc002;
+    const COLOR: u16 = 0x0001;
+    /// Neither a global nor a spot color.
+    const NORMAL: u16 = 2;
+
+    fn block(bytes: &mut Vec<u8>, kind: u16, name: Option<&str>, data: &[u8]) {
+        let mut body = Vec::new();
+
+        if let Some(name) = name {
+            let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+
+            body.extend((name.len() as u16).to_be_bytes());
+            body.extend(name.into_iter().flat_map(u16::to_be_bytes));
+        }
+
+        body.extend(data);
+
+        bytes.extend(kind.to_be_bytes());
+        bytes.extend((body.len() as u32).to_be_bytes());
+        bytes.extend(body);
+    }
+
+    let mut blocks = Vec::new();
+    let mut count: u32 = 0;
+
+    for (usage, swatches) in swatches(extended) {
+        block(&mut blocks, GROUP_START, Some(usage.label()), &[]);
+
+        for (swatch, color) in &swatches {
+            let data: Vec<u8> = b"RGB "
+                .iter()
+                .copied()
+                .chain(
+                    [color.r, color.g, color.b]
+                        .into_iter()
+                        .flat_map(f32::to_be_bytes),
+                )
+                .chain(NORMAL.to_be_bytes())
+                .collect();
+
+            block(&mut blocks, COLOR, Some(swatch), &data);
+        }
+
+        block(&mut blocks, GROUP_END, None, &[]);
+        count += swatches.len() as u32 + 2;
+    }
+
+    let mut bytes = b"ASEF".to_vec();
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(count.to_be_bytes());
+    bytes.extend(blocks);
+
+    bytes
+}
+
+/// A [W3C design tokens](https://tr.designtokens.org/format/) file, with a
+/// group per usage holding the color and text token of every variant.
+/// The author and tags, which have no place of their own in the format,
+/// are kept in its `$extensions`.
+fn tokens(metadata: &Metadata, extended: &Extended) -> String {
+    let token = |color| json!({ "$type": "color", "$value": to_hex(color) });
+
+    let description = if metadata.description.is_empty() {
+        metadata.name.clone()
+    } else {
+        format!("{}: {}", metadata.name, metadata.description)
+    };
+
+    let mut groups = Map::new();
+    let _ = groups.insert("$description".to_owned(), Value::from(description));
+
+    if !metadata.author.is_empty() || !metadata.tags.is_empty() {
+        let _ = groups.insert(
+            "$extensions".to_owned(),
+            json!({
+                "iced_theme_viewer": {
+                    "author": metadata.author,
+                    "tags": metadata.tags,
+                }
+            }),
+        );
+    }
+
+    for usage in Usage::ALL {
+        let variants: Map<String, Value> = Variant::ALL
+            .into_iter()
+            .map(|variant| {
+                let pair = pair(extended, usage, variant);
+
+                (
+                    variant.label().to_lowercase(),
+                    json!({ "color": token(pair.color), "text": token(pair.text) }),
+                )
+            })
+            .collect();
+
+        let _ = groups.insert(usage.label().to_lowercase(), Value::Object(variants));
+    }
+
+    let mut tokens = serde_json::to_string_pretty(&groups).unwrap_or_default();
+    tokens.push('\n');
+
+    tokens
+}
+
+/// A Rust module defining the palette as a constant, named like the theme.
+fn rust(metadata: &Metadata, extended: &Extended) -> String {
+    let name = &metadata.name;
+
+    let group = |usage: Usage| {
+        let pairs: String = Variant::ALL
+            .into_iter()
+            .map(|variant| {
+                let pair = pair(extended, usage, variant);
+
+                format!(
+                    "        {}: Pair {{\n            color: {},\n            text: {},\n        }},\n",
+                    variant.label().to_lowercase(),
+                    color::Format::ColorMacro.format(pair.color),
+                    color::Format::ColorMacro.format(pair.text),
+                )
+            })
+            .collect();
+
+        format!(
+            "    {}: {} {{\n{pairs}    }},\n",
+            usage.label().to_lowercase(),
+            usage.label()
+        )
+    };
+
+    let groups: String = Usage::ALL.into_iter().map(group).collect();
+
+    let details: String = header(metadata)
+        .skip(1)
+        .map(|line| format!("//!\n//! {line}\n"))
+        .collect();
+
+    let mut constant = slug(name).replace('-', "_").to_uppercase();
+
+    if !constant.starts_with(char::is_alphabetic) {
+        constant.insert(0, '_');
+    }
+
+    format!(
+        "//! The {name} palette.\n\
+         {details}\
+         use iced::{{\n    \
+             color,\n    \
+             theme::palette::{{Background, Danger, Extended, Pair, Primary, Secondary, Success}},\n\
+         }};\n\
+         \n\
+         pub const {constant}: Extended = Extended {{\n\
+         {groups}    \
+         is_dark: {},\n\
+         }};\n",
+        extended.is_dark
+    )
+}
+
+/// Turns a theme name like `Tokyo Night Storm` into `tokyo-night-storm`.
+pub fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub async fn save(path: PathBuf, contents: Vec<u8>) -> Result<PathBuf, Error> {
+    tokio::fs::write(&path, contents).await?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::theme::Palette;
+
+    /// A white and black palette, so the background's hex values are known.
+    fn extended() -> Extended {
+        Extended::generate(Palette {
+            background: Color::WHITE,
+            text: Color::BLACK,
+            ..Palette::LIGHT
+        })
+    }
+
+    fn metadata() -> Metadata {
+        Metadata {
+            name: "Paper".to_owned(),
+            author: "Ada".to_owned(),
+            description: String::new(),
+            tags: vec!["light".to_owned(), "plain".to_owned()],
+        }
+    }
+
+    fn export(format: Format) -> String {
+        String::from_utf8(format.export(&metadata(), &extended())).unwrap()
+    }
+
+    #[test]
+    fn css_layout() {
+        let css = export(Format::Css);
+
+        assert!(css.starts_with("/* Paper */\n/* By Ada */\n/* Tags: light, plain */\n:root {\n"));
+        assert!(css.ends_with(";\n}\n"));
+        assert!(css.contains("\n  --iced-background-base: #ffffff;\n"));
+        assert!(css.contains("\n  --iced-background-base-text: #000000;\n"));
+        assert_eq!(css.matches("  --iced-").count(), 30);
+    }
+
+    #[test]
+    fn scss_layout() {
+        let scss = export(Format::Scss);
+
+        assert!(scss.starts_with(
+            "// Paper\n// By Ada\n// Tags: light, plain\n$iced-background-base: #ffffff;\n"
+        ));
+        assert!(scss.contains("\n$iced-background-base-text: #000000;\n"));
+        assert!(scss.contains("\n$iced-danger-strong-text: "));
+        assert_eq!(
+            scss.lines()
+                .filter(|line| line.starts_with("$iced-"))
+                .count(),
+            30
+        );
+    }
+
+    #[test]
+    fn gpl_layout() {
+        let gpl = export(Format::Gpl);
+
+        assert!(gpl.starts_with(
+            "GIMP Palette\nName: Paper\nColumns: 6\n#\n# By Ada\n# Tags: light, plain\n"
+        ));
+        assert!(gpl.contains("\n255 255 255\tBackground Base\n"));
+        assert!(gpl.contains("\n  0   0   0\tBackground Base Text\n"));
+        assert_eq!(gpl.lines().filter(|line| line.contains('\t')).count(), 30);
+    }
+
+    #[test]
+    fn tokens_layout() {
+        let tokens: Value = serde_json::from_str(&export(Format::Tokens)).unwrap();
+
+        assert_eq!(tokens["$description"], "Paper");
+        assert_eq!(tokens["$extensions"]["iced_theme_viewer"]["author"], "Ada");
+        assert_eq!(
+            tokens["$extensions"]["iced_theme_viewer"]["tags"],
+            json!(["light", "plain"])
+        );
+        assert_eq!(
+            tokens["background"]["base"],
+            json!({
+                "color": { "$type": "color", "$value": "#ffffff" },
+                "text": { "$type": "color", "$value": "#000000" },
+            })
+        );
+
+        for usage in Usage::ALL {
+            let group = &tokens[usage.label().to_lowercase()];
+
+            for variant in Variant::ALL {
+                let pair = &group[variant.label().to_lowercase()];
+
+                assert_eq!(pair["color"]["$type"], "color", "{usage:?} {variant:?}");
+                assert_eq!(pair["text"]["$type"], "color", "{usage:?} {variant:?}");
+            }
+        }
+    }
+
+    /// The Rust export of Nord, compiled as part of the tests.
+    mod nord;
+
+    #[test]
+    fn rust_module() {
+        let metadata = Metadata {
+            name: "Nord".to_owned(),
+            author: "Arctic Ice Studio".to_owned(),
+            description: "An arctic, north-bluish color palette".to_owned(),
+            tags: Vec::new(),
+        };
+        let extended = iced::Theme::Nord.extended_palette();
+
+        assert_eq!(
+            rust(&metadata, extended),
+            include_str!("export/tests/nord.rs")
+        );
+
+        // The constant holds the palette, to the precision of its hex values
+        for usage in Usage::ALL {
+            for variant in Variant::ALL {
+                let exported = pair(&nord::NORD, usage, variant);
+                let pair = pair(extended, usage, variant);
+
+                assert_eq!(
+                    (exported.color.into_rgba8(), exported.text.into_rgba8()),
+                    (pair.color.into_rgba8(), pair.text.into_rgba8()),
+                    "{usage:?} {variant:?}"
+                );
+            }
+        }
+
+        assert_eq!(nord::NORD.is_dark, extended.is_dark);
+    }
+
+    fn utf16(name: &str) -> Vec<u8> {
+        name.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_be_bytes)
+            .collect()
+    }
+
+    #[test]
+    fn ase_layout() {
+        let bytes = ase(&extended());
+
+        // The signature, version 1.0 and 5 groups of 6 colors between a
+        // start and an end block
+        assert_eq!(&bytes[..12], b"ASEF\0\x01\0\0\0\0\0\x28");
+
+        let mut group = vec![0xc0, 0x01, 0, 0, 0, 24, 0, 11];
+        group.extend(utf16("Background"));
+        assert_eq!(&bytes[12..12 + group.len()], group);
+
+        let mut color = vec![0x00, 0x01, 0, 0, 0, 52, 0, 16];
+        color.extend(utf16("Background Base"));
+        color.extend(b"RGB ");
+        color.extend([1.0f32; 3].into_iter().flat_map(f32::to_be_bytes));
+        color.extend([0, 2]);
+
+        let start = 12 + group.len();
+        assert_eq!(&bytes[start..start + color.len()], color);
+
+        assert_eq!(&bytes[bytes.len() - 6..], [0xc0, 0x02, 0, 0, 0, 0]);
+
+        // Every block's length covers exactly its body
+        let mut offset = 12;
+        let mut blocks = 0;
+
+        while offset < bytes.len() {
+            let length = u32::from_be_bytes(bytes[offset + 2..offset + 6].try_into().unwrap());
+            offset += 6 + length as usize;
+            blocks += 1;
+        }
+
+        assert_eq!(offset, bytes.len());
+        assert_eq!(blocks, 40);
+    }
+}
@@ -0,0 +1,83 @@
+//! The Nord palette.
+//!
+//! By Arctic Ice Studio
+//!
+//! An arctic, north-bluish color palette
+use iced::{
+    color,
+    theme::palette::{Background, Danger, Extended, Pair, Primary, Secondary, Success},
+};
+
+pub const NORD: Extended = Extended {
+    background: Background {
+        base: Pair {
+            color: color!(0x2e3440),
+            text: color!(0xeceff4),
+        },
+        weak: Pair {
+            color: color!(0x6c6f75),
+            text: color!(0xffffff),
+        },
+        strong: Pair {
+            color: color!(0xa0a3a8),
+            text: color!(0x000000),
+        },
+    },
+    primary: Primary {
+        base: Pair {
+            color: color!(0x8fbcbb),
+            text: color!(0x000000),
+        },
+        weak: Pair {
+            color: color!(0x749899),
+            text: color!(0x000000),
+        },
+        strong: Pair {
+            color: color!(0x6fa9a8),
+            text: color!(0x000000),
+        },
+    },
+    secondary: Secondary {
+        base: Pair {
+            color: color!(0x797c82),
+            text: color!(0x000000),
+        },
+        weak: Pair {
+            color: color!(0x8a8d93),
+            text: color!(0x000000),
+        },
+        strong: Pair {
+            color: color!(0xa7a9af),
+            text: color!(0x000000),
+        },
+    },
+    success: Success {
+        base: Pair {
+            color: color!(0xa3be8c),
+            text: color!(0x000000),
+        },
+        weak: Pair {
+            color: color!(0x849a75),
+            text: color!(0x000000),
+        },
+        strong: Pair {
+            color: color!(0x89ac6b),
+            text: color!(0x000000),
+        },
+    },
+    danger: Danger {
+        base: Pair {
+            color: color!(0xbf616a),
+            text: color!(0x000000),
+        },
+        weak: Pair {
+            color: color!(0x9a535c),
+            text: color!(0xffffff),
+        },
+        strong: Pair {
+            color: color!(0xce858c),
+            text: color!(0x000000),
+        },
+    },
+    is_dark: true,
+};
@@ -0,0 +1,232 @@
+use crate::{Usage, Variant, color, pair, with_locked};
+use iced::theme::palette::{Extended, Palette};
+use std::fmt;
+
+/// How many random palettes to try before giving up on the constraints.
+const ATTEMPTS: usize = 512;
+
+/// The smallest OKLCH hue angle allowed between success and danger.
+const MIN_HUE_DISTANCE: f32 = 60.0;
+
+/// A small SplitMix64 generator, so that a seed always reproduces the same
+/// theme regardless of platform or dependency versions.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value in `[low, high)`.
+    fn range(&mut self, low: f32, high: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        low + (high - low) * unit
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinContrast {
+    Large,
+    #[default]
+    Aa,
+    Aaa,
+}
+
+impl MinContrast {
+    pub const ALL: [MinContrast; 3] = [MinContrast::Large, MinContrast::Aa, MinContrast::Aaa];
+
+    pub fn ratio(self) -> f32 {
+        match self {
+            MinContrast::Large => 3.0,
+            MinContrast::Aa => 4.5,
+            MinContrast::Aaa => 7.0,
+        }
+    }
+}
+
+impl fmt::Display for MinContrast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinContrast::Large => write!(f, "AA Large (3:1)"),
+            MinContrast::Aa => write!(f, "AA (4.5:1)"),
+            MinContrast::Aaa => write!(f, "AAA (7:1)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    pub min_contrast: MinContrast,
+    pub is_dark: bool,
+    /// Cells which are copied from the current palette instead of generated.
+    pub locked: Vec<(Usage, Variant)>,
+}
+
+/// Generates a random [`Extended`] palette satisfying the given constraints.
+///
+/// The same seed, constraints and current palette always produce the same
+/// result. Returns `None` if no palette was found within a fixed number of
+/// attempts; some combinations, such as AAA contrast on a dark background,
+/// cannot be reached with the weak and strong colors iced derives.
+pub fn generate(seed: u64, constraints: &Constraints, current: &Extended) -> Option<Extended> {
+    let mut rng = Rng::new(seed);
+
+    (0..ATTEMPTS).find_map(|_| {
+        let generated = Extended::generate(random_palette(&mut rng, constraints.is_dark));
+
+        if generated.is_dark != constraints.is_dark {
+            return None;
+        }
+
+        let extended = with_locked(generated, current, &constraints.locked);
+
+        satisfies(&extended, constraints).then_some(extended)
+    })
+}
+
+fn random_palette(rng: &mut Rng, is_dark: bool) -> Palette {
+    let (background, text) = if is_dark {
+        (rng.range(0.08, 0.32), rng.range(0.85, 0.99))
+    } else {
+        (rng.range(0.92, 1.0), rng.range(0.1, 0.3))
+    };
+
+    let neutral = rng.range(0.0, 360.0);
+    let background = color::from_oklch(background, rng.range(0.0, 0.04), neutral);
+    let text = color::from_oklch(text, rng.range(0.0, 0.03), neutral);
+
+    let mut accent = || {
+        let hue = rng.range(0.0, 360.0);
+        color::from_oklch(rng.range(0.4, 0.85), rng.range(0.08, 0.2), hue)
+    };
+
+    Palette {
+        background,
+        text,
+        primary: accent(),
+        success: accent(),
+        danger: accent(),
+    }
+}
+
+fn satisfies(extended: &Extended, constraints: &Constraints) -> bool {
+    let min = constraints.min_contrast.ratio();
+
+    let readable = Usage::ALL.into_iter().all(|usage| {
+        Variant::ALL.into_iter().all(|variant| {
+            if constraints.locked.contains(&(usage, variant)) {
+                return true;
+            }
+
+            let pair = pair(extended, usage, variant);
+            color::contrast(pair.color, pair.text) >= min
+        })
+    });
+
+    readable
+        && color::hue_distance(extended.success.base.color, extended.danger.base.color)
+            >= MIN_HUE_DISTANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraints(min_contrast: MinContrast, is_dark: bool) -> Constraints {
+        Constraints {
+            min_contrast,
+            is_dark,
+            locked: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn same_seed_same_palette() {
+        let constraints = constraints(MinContrast::Aa, true);
+        let current = Extended::generate(Palette::DARK);
+
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(
+                generate(seed, &constraints, &current),
+                generate(seed, &constraints, &current),
+                "seed {seed}",
+            );
+        }
+    }
+
+    #[test]
+    fn constraints_are_met() {
+        let current = Extended::generate(Palette::LIGHT);
+
+        for min_contrast in MinContrast::ALL {
+            for is_dark in [false, true] {
+                let constraints = constraints(min_contrast, is_dark);
+                let generated: Vec<_> = (0..16)
+                    .filter_map(|seed| generate(seed, &constraints, &current))
+                    .collect();
+
+                // AAA contrast cannot be reached on a dark background
+                if min_contrast != MinContrast::Aaa || !is_dark {
+                    assert!(!generated.is_empty(), "{min_contrast}, dark: {is_dark}");
+                }
+
+                for extended in generated {
+                    assert_eq!(extended.is_dark, is_dark);
+
+                    for usage in Usage::ALL {
+                        for variant in Variant::ALL {
+                            let pair = pair(&extended, usage, variant);
+
+                            assert!(
+                                color::contrast(pair.color, pair.text) >= min_contrast.ratio(),
+                                "{min_contrast}: {} {}",
+                                usage.label(),
+                                variant.label(),
+                            );
+                        }
+                    }
+
+                    assert!(
+                        color::hue_distance(
+                            extended.success.base.color,
+                            extended.danger.base.color
+                        ) >= MIN_HUE_DISTANCE
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn locked_cells_are_kept() {
+        let current = Extended::generate(Palette::DRACULA);
+        let constraints = Constraints {
+            locked: vec![
+                (Usage::Primary, Variant::Base),
+                (Usage::Danger, Variant::Strong),
+            ],
+            ..constraints(MinContrast::Large, true)
+        };
+
+        let extended = (0..16)
+            .find_map(|seed| generate(seed, &constraints, &current))
+            .expect("a palette with locked cells");
+
+        for &(usage, variant) in &constraints.locked {
+            assert_eq!(
+                pair(&extended, usage, variant),
+                pair(&current, usage, variant)
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Loading themes from files written for other tools.
+use crate::theme_file::{self, Error, ThemeFile};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+mod base16;
+pub mod terminal;
+mod vscode;
+
+#[derive(Debug, Clone)]
+pub struct Imported {
+    pub file: ThemeFile,
+    /// How the file was mapped onto the palette, for formats which do not
+    /// describe every cell.
+    pub report: Option<String>,
+    /// The colors of a terminal scheme, kept so that they can be mapped
+    /// onto the palette differently.
+    pub terminal: Option<terminal::Colors>,
+}
+
+impl From<ThemeFile> for Imported {
+    fn from(file: ThemeFile) -> Self {
+        Self {
+            file,
+            report: None,
+            terminal: None,
+        }
+    }
+}
+
+impl Imported {
+    fn terminal(colors: terminal::Colors, path: &Path) -> Self {
+        let name = colors.name.clone().unwrap_or_else(|| {
+            path.file_stem().map_or_else(
+                || crate::CUSTOM.to_owned(),
+                |stem| stem.to_string_lossy().into_owned(),
+            )
+        });
+
+        let extended = colors.extended(terminal::Mapping::default());
+
+        Self {
+            file: ThemeFile::new(name, &extended, &[]),
+            report: None,
+            terminal: Some(colors),
+        }
+    }
+}
+
+/// Loads a theme file, picking the format from its name.
+pub async fn load(path: PathBuf) -> Result<Imported, Error> {
+    let contents = tokio::fs::read_to_string(&path).await?;
+
+    parse(&path, &contents)
+}
+
+/// Parses the contents of the theme file at `path`.
+pub fn parse(path: &Path, contents: &str) -> Result<Imported, Error> {
+    let name = path
+        .file_name()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let terminal = |colors: Result<terminal::Colors, Error>| {
+        colors.map(|colors| Imported::terminal(colors, path))
+    };
+
+    match path.extension().and_then(OsStr::to_str) {
+        Some("yaml" | "yml") => base16::parse(contents).map(Imported::from),
+        Some("jsonc") => vscode::parse(contents),
+        Some("toml") => terminal(terminal::toml(contents)),
+        Some("conf") => terminal(terminal::kitty(contents)),
+        _ if name.starts_with(".xresources") || name.starts_with(".xdefaults") => {
+            terminal(terminal::xresources(contents))
+        }
+        _ if name.ends_with("color-theme.json") => vscode::parse(contents),
+        _ if terminal::is_xresources(contents) => terminal(terminal::xresources(contents)),
+        _ => theme_file::parse(contents).map(Imported::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_format() {
+        let resources = "*.background: #000000\n*.foreground: rgb:ff/ff/ff\n";
+
+        for path in [".Xresources", ".Xdefaults", "nord", "colors.txt"] {
+            let imported = parse(Path::new(path), resources).unwrap();
+            assert!(imported.terminal.is_some(), "{path}");
+        }
+
+        let kitty = "background #000000\nforeground #ffffff\n";
+        assert!(
+            parse(Path::new("theme.conf"), kitty)
+                .unwrap()
+                .terminal
+                .is_some()
+        );
+        assert!(matches!(
+            parse(Path::new("theme.json"), kitty),
+            Err(Error::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+//! [base16] and base24 schemes, in both the original flat layout and the
+//! newer one with a nested `palette`.
+//!
+//! [base16]: https://github.com/tinted-theming/home
+use crate::theme_file::{Error, ThemeFile};
+use iced::{
+    Color,
+    theme::{Palette, palette::Extended},
+};
+use std::collections::HashMap;
+
+pub fn parse(contents: &str) -> Result<ThemeFile, Error> {
+    let mut name = None;
+    let mut author = String::new();
+    let mut description = String::new();
+    let mut colors = HashMap::new();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let key = key.trim();
+        let value = unquote(value);
+
+        match key {
+            "scheme" | "name" => {
+                name = name.or(Some(value.to_owned()));
+            }
+            "author" => value.clone_into(&mut author),
+            "description" => value.clone_into(&mut description),
+            _ if key.len() == 6 && key.starts_with("base") => {
+                let color = value
+                    .is_ascii()
+                    .then(|| Color::parse(value))
+                    .flatten()
+                    .ok_or_else(|| Error::Parse(format!("invalid color for {key}: {value:?}")))?;
+
+                let _ = colors.insert(key.to_ascii_lowercase(), color);
+            }
+            _ => {}
+        }
+    }
+
+    let color = |key: &str| {
+        colors
+            .get(key)
+            .copied()
+            .ok_or_else(|| Error::Parse(format!("missing {key}")))
+    };
+
+    // Following the base16 styling guidelines: default background and
+    // foreground, then functions, strings and variables.
+    let palette = Palette {
+        background: color("base00")?,
+        text: color("base05")?,
+        primary: color("base0d")?,
+        success: color("base0b")?,
+        danger: color("base08")?,
+    };
+
+    let name = name
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| crate::CUSTOM.to_owned());
+
+    let mut file = ThemeFile::new(name, &Extended::generate(palette), &[]);
+    file.metadata.author = author;
+    file.metadata.description = description;
+
+    Ok(file)
+}
+
+/// Strips quotes and trailing comments from a YAML scalar.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+
+    for quote in ['"', '\''] {
+        if let Some(quoted) = value.strip_prefix(quote) {
+            return quoted.split(quote).next().unwrap_or_default();
+        }
+    }
+
+    value.split(" #").next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Usage, Variant, pair};
+    use iced::color;
+
+    const FLAT: &str = r#"scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666" # red
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+
+    const NESTED: &str = "system: \"base24\"
+name: 'Dracula'
+author: \"FredHappyface (https://github.com/fredHappyface)\"
+description: 'A dark theme'
+variant: \"dark\"
+palette:
+  base00: \"#282936\"
+  base01: \"#3a3c4e\"
+  base02: \"#4d4f68\"
+  base03: \"#626483\"
+  base04: \"#62d6e8\"
+  base05: \"#e9e9f4\"
+  base06: \"#f1f2f8\"
+  base07: \"#f7f7fb\"
+  base08: \"#ea51b2\"
+  base09: \"#b45bcf\"
+  base0A: \"#00f769\"
+  base0B: \"#ebff87\"
+  base0C: \"#a1efe4\"
+  base0D: \"#62d6e8\"
+  base0E: \"#b45bcf\"
+  base0F: \"#00f769\"
+  base10: \"#1e1f29\"
+  base11: \"#14151b\"
+";
+
+    fn color(file: &ThemeFile, usage: Usage) -> Color {
+        pair(&file.extended(), usage, Variant::Base).color
+    }
+
+    #[test]
+    fn flat_layout() {
+        let file = parse(FLAT).unwrap();
+
+        assert_eq!(file.metadata.name, "Tomorrow Night");
+        assert_eq!(
+            file.metadata.author,
+            "Chris Kempson (http://chriskempson.com)"
+        );
+        assert_eq!(color(&file, Usage::Background), color!(0x1d1f21));
+        assert_eq!(color(&file, Usage::Primary), color!(0x81a2be));
+        assert_eq!(color(&file, Usage::Success), color!(0xb5bd68));
+        assert_eq!(color(&file, Usage::Danger), color!(0xcc6666));
+        assert!(file.is_dark);
+    }
+
+    #[test]
+    fn nested_layout() {
+        let file = parse(NESTED).unwrap();
+
+        assert_eq!(file.metadata.name, "Dracula");
+        assert_eq!(file.metadata.description, "A dark theme");
+        assert_eq!(color(&file, Usage::Background), color!(0x282936));
+        assert_eq!(color(&file, Usage::Primary), color!(0x62d6e8));
+        assert_eq!(color(&file, Usage::Success), color!(0xebff87));
+        assert_eq!(color(&file, Usage::Danger), color!(0xea51b2));
+    }
+
+    #[test]
+    fn unnamed_scheme() {
+        let file = parse(&FLAT.replace("scheme: \"Tomorrow Night\"", "scheme: \"\"")).unwrap();
+
+        assert_eq!(file.metadata.name, crate::CUSTOM);
+    }
+
+    #[test]
+    fn errors() {
+        let missing = FLAT.replace("base0D: \"81a2be\"\n", "");
+        assert!(matches!(
+            parse(&missing),
+            Err(Error::Parse(error)) if error == "missing base0d"
+        ));
+
+        let invalid = FLAT.replace("cc6666", "cc66zz");
+        assert!(matches!(
+            parse(&invalid),
+            Err(Error::Parse(error)) if error.contains("base08")
+        ));
+
+        let non_ascii = FLAT.replace("cc6666", "aéée");
+        assert!(matches!(parse(&non_ascii), Err(Error::Parse(_))));
+
+        assert!(matches!(parse(""), Err(Error::Parse(_))));
+    }
+}
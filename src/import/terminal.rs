@@ -0,0 +1,500 @@
+//! Terminal emulator color schemes: Alacritty and WezTerm TOML, Kitty
+//! configs and Xresources.
+//!
+//! Only the background, the foreground and the 16 ANSI colors are read.
+//! Which ANSI colors become the primary, success and danger colors is up to
+//! a [`Mapping`].
+use crate::{Usage, theme_file::Error};
+use iced::{
+    Color,
+    theme::{Palette, palette::Extended},
+};
+use std::{collections::HashMap, fmt};
+use toml_edit::{DocumentMut, Item};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    pub name: Option<String>,
+    pub background: Color,
+    pub foreground: Color,
+    /// The 8 normal colors followed by their bright versions.
+    pub ansi: [Option<Color>; 16],
+}
+
+impl Colors {
+    pub fn extended(&self, mapping: Mapping) -> Extended {
+        let color = |ansi: Ansi, default| {
+            let slot = ansi as usize;
+            self.ansi[slot].or(self.ansi[slot % 8]).unwrap_or(default)
+        };
+
+        Extended::generate(Palette {
+            background: self.background,
+            text: self.foreground,
+            primary: color(mapping.primary, Palette::DARK.primary),
+            success: color(mapping.success, Palette::DARK.success),
+            danger: color(mapping.danger, Palette::DARK.danger),
+        })
+    }
+}
+
+/// Which ANSI colors the accent colors of the palette are taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub primary: Ansi,
+    pub success: Ansi,
+    pub danger: Ansi,
+}
+
+impl Default for Mapping {
+    fn default() -> Self {
+        Self {
+            primary: Ansi::Blue,
+            success: Ansi::Green,
+            danger: Ansi::Red,
+        }
+    }
+}
+
+impl Mapping {
+    /// The usages whose base color comes from an ANSI color.
+    pub const USAGES: [Usage; 3] = [Usage::Primary, Usage::Success, Usage::Danger];
+
+    pub fn get(&self, usage: Usage) -> Option<Ansi> {
+        match usage {
+            Usage::Primary => Some(self.primary),
+            Usage::Success => Some(self.success),
+            Usage::Danger => Some(self.danger),
+            Usage::Background | Usage::Secondary => None,
+        }
+    }
+
+    pub fn set(&mut self, usage: Usage, ansi: Ansi) {
+        match usage {
+            Usage::Primary => self.primary = ansi,
+            Usage::Success => self.success = ansi,
+            Usage::Danger => self.danger = ansi,
+            Usage::Background | Usage::Secondary => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ansi {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl Ansi {
+    pub const ALL: [Ansi; 16] = [
+        Ansi::Black,
+        Ansi::Red,
+        Ansi::Green,
+        Ansi::Yellow,
+        Ansi::Blue,
+        Ansi::Magenta,
+        Ansi::Cyan,
+        Ansi::White,
+        Ansi::BrightBlack,
+        Ansi::BrightRed,
+        Ansi::BrightGreen,
+        Ansi::BrightYellow,
+        Ansi::BrightBlue,
+        Ansi::BrightMagenta,
+        Ansi::BrightCyan,
+        Ansi::BrightWhite,
+    ];
+
+    /// The names Alacritty gives to the normal colors.
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+}
+
+impl fmt::Display for Ansi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slot = *self as usize;
+        let name = Ansi::NAMES[slot % 8];
+
+        if slot < 8 {
+            write!(f, "{}{}", name[..1].to_uppercase(), &name[1..])
+        } else {
+            write!(f, "Bright {name}")
+        }
+    }
+}
+
+/// Parses an Alacritty or WezTerm TOML color scheme.
+pub fn toml(contents: &str) -> Result<Colors, Error> {
+    let document: DocumentMut = contents
+        .parse()
+        .map_err(|error: toml_edit::TomlError| Error::Parse(error.to_string()))?;
+
+    let colors = document
+        .get("colors")
+        .ok_or_else(|| Error::Parse("missing colors".to_owned()))?;
+
+    let color = |item: Option<&Item>| item.and_then(Item::as_str).and_then(parse_color);
+
+    // Alacritty nests its colors in tables, WezTerm keeps them in arrays
+    let primary = colors.get("primary");
+    let background = color(primary.and_then(|primary| primary.get("background")))
+        .or_else(|| color(colors.get("background")));
+    let foreground = color(primary.and_then(|primary| primary.get("foreground")))
+        .or_else(|| color(colors.get("foreground")));
+
+    let mut ansi = [None; 16];
+
+    for (offset, (table, array)) in [(0, ("normal", "ansi")), (8, ("bright", "brights"))] {
+        if let Some(table) = colors.get(table) {
+            for (slot, name) in Ansi::NAMES.into_iter().enumerate() {
+                ansi[offset + slot] = color(table.get(name));
+            }
+        } else if let Some(array) = colors.get(array).and_then(Item::as_array) {
+            for (slot, value) in array.iter().take(8).enumerate() {
+                ansi[offset + slot] = value.as_str().and_then(parse_color);
+            }
+        }
+    }
+
+    let name = document
+        .get("metadata")
+        .and_then(|metadata| metadata.get("name"))
+        .and_then(Item::as_str)
+        .map(ToOwned::to_owned);
+
+    colors_from(name, background, foreground, ansi)
+}
+
+/// Parses a Kitty config or theme.
+pub fn kitty(contents: &str) -> Result<Colors, Error> {
+    let mut name = None;
+    let mut values = HashMap::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if let Some(comment) = line.strip_prefix('#') {
+            // Themes from kitty-themes start with a `## name: ...` header
+            if let Some(value) = comment.trim_start_matches('#').trim().strip_prefix("name:") {
+                name = Some(value.trim().to_owned());
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(char::is_whitespace) {
+            let _ = values.insert(key, value.trim());
+        }
+    }
+
+    from_values(name, &values)
+}
+
+/// Whether a file looks like X resources, setting a color such as
+/// `*color0` or `*.background`.
+pub fn is_xresources(contents: &str) -> bool {
+    contents.lines().any(|line| {
+        let Some((resource, _)) = line.trim().split_once(':') else {
+            return false;
+        };
+
+        let key = resource.rsplit(['.', '*']).next().unwrap_or_default();
+
+        resource.contains(['.', '*'])
+            && !resource.contains(|c: char| c.is_whitespace() || c == '"')
+            && (key == "background" || key == "foreground" || key.starts_with("color"))
+    })
+}
+
+/// Parses X resources, such as an `.Xresources` file.
+pub fn xresources(contents: &str) -> Result<Colors, Error> {
+    let mut defines = HashMap::new();
+    let mut values = HashMap::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if let Some(define) = line.strip_prefix("#define") {
+            if let Some((key, value)) = define.trim().split_once(char::is_whitespace) {
+                let _ = defines.insert(key, value.trim());
+            }
+            continue;
+        }
+
+        if line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((resource, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        // Only the last component matters, as in `URxvt*color0` or `*.background`
+        let key = resource
+            .rsplit(['.', '*'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let value = value.trim();
+
+        let _ = values.insert(key, defines.get(value).copied().unwrap_or(value));
+    }
+
+    from_values(None, &values)
+}
+
+/// Reads the `background`, `foreground` and `color0` to `color15` keys
+/// shared by Kitty and X resources.
+fn from_values(name: Option<String>, values: &HashMap<&str, &str>) -> Result<Colors, Error> {
+    let color = |key: &str| values.get(key).copied().and_then(parse_color);
+
+    let ansi = std::array::from_fn(|slot| color(&format!("color{slot}")));
+
+    colors_from(name, color("background"), color("foreground"), ansi)
+}
+
+fn colors_from(
+    name: Option<String>,
+    background: Option<Color>,
+    foreground: Option<Color>,
+    ansi: [Option<Color>; 16],
+) -> Result<Colors, Error> {
+    Ok(Colors {
+        name: name.filter(|name| !name.is_empty()),
+        background: background.ok_or_else(|| Error::Parse("missing background".to_owned()))?,
+        foreground: foreground.ok_or_else(|| Error::Parse("missing foreground".to_owned()))?,
+        ansi,
+    })
+}
+
+/// Parses `#rrggbb` as well as the `0xrrggbb` some Alacritty configs use,
+/// and the `rgb:rr/gg/bb` of X resources.
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().trim_matches(['"', '\'']);
+
+    if let Some(channels) = value.strip_prefix("rgb:") {
+        return x_color(channels);
+    }
+
+    let hex = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix('#'))?;
+
+    hex.is_ascii().then(|| Color::parse(hex)).flatten()
+}
+
+/// Parses the `rr/gg/bb` of an X color, where each channel has from one to
+/// four hex digits.
+fn x_color(channels: &str) -> Option<Color> {
+    let channels = channels
+        .split('/')
+        .map(|channel| {
+            if !(1..=4).contains(&channel.len()) || !channel.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                return None;
+            }
+
+            let max = (1u32 << (4 * channel.len())) - 1;
+            let value = u32::from_str_radix(channel, 16).ok()?;
+
+            Some(value as f32 / max as f32)
+        })
+        .collect::<Option<Vec<f32>>>()?;
+
+    match channels[..] {
+        [r, g, b] => Some(Color::from_rgb(r, g, b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::color;
+
+    const ALACRITTY: &str = r##"
+[metadata]
+name = "Gruvbox"
+
+[colors.primary]
+background = "#282828"
+foreground = '0xebdbb2'
+
+[colors.normal]
+black = "#282828"
+red = "#cc241d"
+green = "#98971a"
+blue = "#458588"
+
+[colors.bright]
+blue = "#83a598"
+"##;
+
+    const WEZTERM: &str = r##"
+[colors]
+background = "#1e1e2e"
+foreground = "#cdd6f4"
+ansi = ["#45475a", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#bac2de"]
+brights = ["#585b70", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#a6adc8"]
+"##;
+
+    const KITTY: &str = "## name: Tokyo Night
+## author: enkia
+
+background #1a1b26
+foreground   #c0caf5
+selection_background #33467c
+
+# black
+color0 #15161e
+color1 #f7768e
+color2 #9ece6a
+color4 #7aa2f7
+color12 #7dcfff
+";
+
+    const XRESOURCES: &str = "! Nord
+#define nord0 #2E3440
+#define nord11 #BF616A
+
+*.foreground:   #D8DEE9
+*.background:   nord0
+*color1:        nord11
+URxvt*color2:   rgb:a3/be/8c
+*color4:        rgb:8181/a1a1/c1c1
+*color12:       rgb:8/c/d
+";
+
+    #[test]
+    fn alacritty() {
+        let colors = toml(ALACRITTY).unwrap();
+
+        assert_eq!(colors.name.as_deref(), Some("Gruvbox"));
+        assert_eq!(colors.background, color!(0x282828));
+        assert_eq!(colors.foreground, color!(0xebdbb2));
+        assert_eq!(colors.ansi[Ansi::Red as usize], Some(color!(0xcc241d)));
+        assert_eq!(colors.ansi[Ansi::Yellow as usize], None);
+        assert_eq!(
+            colors.ansi[Ansi::BrightBlue as usize],
+            Some(color!(0x83a598))
+        );
+    }
+
+    #[test]
+    fn wezterm() {
+        let colors = toml(WEZTERM).unwrap();
+
+        assert_eq!(colors.name, None);
+        assert_eq!(colors.background, color!(0x1e1e2e));
+        assert_eq!(colors.ansi[Ansi::Blue as usize], Some(color!(0x89b4fa)));
+        assert_eq!(
+            colors.ansi[Ansi::BrightWhite as usize],
+            Some(color!(0xa6adc8))
+        );
+    }
+
+    #[test]
+    fn kitty_config() {
+        let colors = kitty(KITTY).unwrap();
+
+        assert_eq!(colors.name.as_deref(), Some("Tokyo Night"));
+        assert_eq!(colors.background, color!(0x1a1b26));
+        assert_eq!(colors.foreground, color!(0xc0caf5));
+        assert_eq!(colors.ansi[Ansi::Green as usize], Some(color!(0x9ece6a)));
+        assert_eq!(
+            colors.ansi[Ansi::BrightBlue as usize],
+            Some(color!(0x7dcfff))
+        );
+    }
+
+    #[test]
+    fn x_resources() {
+        let colors = xresources(XRESOURCES).unwrap();
+
+        assert_eq!(colors.background, color!(0x2e3440));
+        assert_eq!(colors.foreground, color!(0xd8dee9));
+        assert_eq!(colors.ansi[Ansi::Red as usize], Some(color!(0xbf616a)));
+
+        let rgb8 = |color: Option<Color>| color.map(Color::into_rgba8);
+        assert_eq!(
+            rgb8(colors.ansi[Ansi::Green as usize]),
+            Some([0xa3, 0xbe, 0x8c, 0xff])
+        );
+        assert_eq!(
+            rgb8(colors.ansi[Ansi::Blue as usize]),
+            Some([0x81, 0xa1, 0xc1, 0xff])
+        );
+        assert_eq!(
+            rgb8(colors.ansi[Ansi::BrightBlue as usize]),
+            Some([0x88, 0xcc, 0xdd, 0xff])
+        );
+
+        assert!(is_xresources(XRESOURCES));
+        assert!(!is_xresources(KITTY));
+        assert!(!is_xresources("{ \"editor.background\": \"#ffffff\" }"));
+    }
+
+    #[test]
+    fn mapping() {
+        let colors = kitty(KITTY).unwrap();
+
+        let extended = colors.extended(Mapping::default());
+        assert_eq!(extended.primary.base.color, color!(0x7aa2f7));
+        assert_eq!(extended.success.base.color, color!(0x9ece6a));
+        assert_eq!(extended.danger.base.color, color!(0xf7768e));
+
+        // Missing bright colors fall back to the normal ones, and missing
+        // normal ones to iced's
+        let extended = colors.extended(Mapping {
+            primary: Ansi::BrightBlue,
+            success: Ansi::BrightGreen,
+            danger: Ansi::Yellow,
+        });
+        assert_eq!(extended.primary.base.color, color!(0x7dcfff));
+        assert_eq!(extended.success.base.color, color!(0x9ece6a));
+        assert_eq!(extended.danger.base.color, Palette::DARK.danger);
+    }
+
+    #[test]
+    fn errors() {
+        let missing = |result: Result<Colors, Error>, expected: &str| {
+            assert!(
+                matches!(&result, Err(Error::Parse(error)) if error == expected),
+                "{result:?}"
+            );
+        };
+
+        missing(toml("[metadata]\nname = \"x\""), "missing colors");
+        missing(
+            toml(&ALACRITTY.replace("background = \"#282828\"", "")),
+            "missing background",
+        );
+        missing(kitty("background #000000"), "missing foreground");
+        missing(
+            xresources(&XRESOURCES.replace("background:   nord0", "background: rgb:12/34")),
+            "missing background",
+        );
+        missing(
+            kitty("background #00000g\nforeground #ffffff"),
+            "missing background",
+        );
+
+        assert!(matches!(toml("[colors"), Err(Error::Parse(_))));
+        assert_eq!(parse_color("rgb:12345/00/00"), None);
+        assert_eq!(parse_color("#ééé"), None);
+    }
+}
@@ -0,0 +1,491 @@
+//! VS Code color themes, usually named `*-color-theme.json`.
+//!
+//! Only the workbench `colors` are read. Each cell takes the first of its
+//! keys present in the theme, and every other cell is generated from the
+//! base colors the way iced does.
+use super::Imported;
+use crate::{
+    CUSTOM, Usage, Variant, pair,
+    theme_file::{Error, ThemeFile},
+    updated_extended,
+};
+use iced::{
+    Color,
+    theme::{
+        Palette,
+        palette::{Extended, Pair},
+    },
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct Theme {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, Option<String>>,
+}
+
+struct Cell {
+    usage: Usage,
+    variant: Variant,
+    colors: &'static [&'static str],
+    texts: &'static [&'static str],
+}
+
+const fn cell(
+    usage: Usage,
+    variant: Variant,
+    colors: &'static [&'static str],
+    texts: &'static [&'static str],
+) -> Cell {
+    Cell {
+        usage,
+        variant,
+        colors,
+        texts,
+    }
+}
+
+/// Candidate keys for every cell, from the most to the least fitting.
+const CELLS: [Cell; 15] = [
+    cell(
+        Usage::Background,
+        Variant::Base,
+        &["editor.background"],
+        &["editor.foreground", "foreground"],
+    ),
+    cell(
+        Usage::Background,
+        Variant::Weak,
+        &[
+            "sideBar.background",
+            "editorWidget.background",
+            "panel.background",
+        ],
+        &["sideBar.foreground", "editorWidget.foreground"],
+    ),
+    cell(
+        Usage::Background,
+        Variant::Strong,
+        &[
+            "input.border",
+            "editorWidget.border",
+            "panel.border",
+            "scrollbarSlider.activeBackground",
+        ],
+        &[],
+    ),
+    cell(
+        Usage::Primary,
+        Variant::Base,
+        &[
+            "button.background",
+            "focusBorder",
+            "textLink.foreground",
+            "terminal.ansiBlue",
+        ],
+        &["button.foreground"],
+    ),
+    cell(
+        Usage::Primary,
+        Variant::Weak,
+        &["editor.selectionBackground", "selection.background"],
+        &["editor.selectionForeground"],
+    ),
+    cell(
+        Usage::Primary,
+        Variant::Strong,
+        &["button.hoverBackground", "terminal.ansiBrightBlue"],
+        &["button.foreground"],
+    ),
+    cell(
+        Usage::Secondary,
+        Variant::Base,
+        &["button.secondaryBackground"],
+        &["button.secondaryForeground"],
+    ),
+    cell(Usage::Secondary, Variant::Weak, &[], &[]),
+    cell(
+        Usage::Secondary,
+        Variant::Strong,
+        &["button.secondaryHoverBackground"],
+        &["button.secondaryForeground"],
+    ),
+    cell(
+        Usage::Success,
+        Variant::Base,
+        &[
+            "terminal.ansiGreen",
+            "gitDecoration.addedResourceForeground",
+            "editorGutter.addedBackground",
+        ],
+        &[],
+    ),
+    cell(Usage::Success, Variant::Weak, &[], &[]),
+    cell(
+        Usage::Success,
+        Variant::Strong,
+        &["terminal.ansiBrightGreen"],
+        &[],
+    ),
+    cell(
+        Usage::Danger,
+        Variant::Base,
+        &[
+            "errorForeground",
+            "editorError.foreground",
+            "terminal.ansiRed",
+        ],
+        &[],
+    ),
+    cell(
+        Usage::Danger,
+        Variant::Weak,
+        &["inputValidation.errorBackground"],
+        &["inputValidation.errorForeground"],
+    ),
+    cell(
+        Usage::Danger,
+        Variant::Strong,
+        &["terminal.ansiBrightRed"],
+        &[],
+    ),
+];
+
+pub fn parse(contents: &str) -> Result<Imported, Error> {
+    let theme: Theme = serde_json::from_str(&strip_comments(contents))
+        .map_err(|error| Error::Parse(error.to_string()))?;
+
+    let colors: HashMap<&str, Color> = theme
+        .colors
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = value.as_deref()?;
+            let color = value.is_ascii().then(|| Color::parse(value)).flatten()?;
+
+            Some((key.as_str(), color))
+        })
+        .collect();
+
+    let mut used = Vec::new();
+
+    let mut find = |keys: &[&'static str], cell: &Cell, text: bool| {
+        let (key, color) = keys
+            .iter()
+            .find_map(|&key| colors.get(key).map(|&color| (key, color)))?;
+
+        used.push(Used {
+            key,
+            usage: cell.usage,
+            variant: cell.variant,
+            text,
+        });
+
+        Some(color)
+    };
+
+    let found = CELLS
+        .each_ref()
+        .map(|cell| (find(cell.colors, cell, false), find(cell.texts, cell, true)));
+
+    let base = |usage| {
+        CELLS
+            .iter()
+            .zip(&found)
+            .find(|(cell, _)| cell.usage == usage && cell.variant == Variant::Base)
+            .map(|(_, &found)| found)
+            .unwrap_or_default()
+    };
+
+    let light = theme.kind.as_deref().map(|kind| {
+        matches!(
+            kind.to_ascii_lowercase().as_str(),
+            "vs" | "light" | "hclight"
+        )
+    });
+    let defaults = if light == Some(true) {
+        Palette::LIGHT
+    } else {
+        Palette::DARK
+    };
+
+    let (background, text) = base(Usage::Background);
+    let background =
+        background.ok_or_else(|| Error::Parse("missing editor.background".to_owned()))?;
+
+    // Themes are opaque in iced, so translucent colors are shown as they
+    // would be on top of the editor.
+    let opaque =
+        |color: Option<Color>, default| color.map_or(default, |color| over(color, background));
+
+    let palette = Palette {
+        background,
+        text: opaque(text, defaults.text),
+        primary: opaque(base(Usage::Primary).0, defaults.primary),
+        success: opaque(base(Usage::Success).0, defaults.success),
+        danger: opaque(base(Usage::Danger).0, defaults.danger),
+    };
+
+    let mut extended = Extended::generate(palette);
+
+    for (cell, &(color, text)) in CELLS.iter().zip(&found) {
+        if color.is_none() && text.is_none() {
+            continue;
+        }
+
+        let color = color.map_or_else(
+            || pair(&extended, cell.usage, cell.variant).color,
+            |color| over(color, background),
+        );
+
+        let pair = match text {
+            Some(text) => Pair {
+                color,
+                text: over(text, color),
+            },
+            // Swaps the text for white or black when it is unreadable
+            None => Pair::new(color, palette.text),
+        };
+
+        extended = updated_extended(extended, pair, cell.usage, cell.variant);
+    }
+
+    if let Some(light) = light {
+        extended.is_dark = !light;
+    }
+
+    let name = theme
+        .name
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| CUSTOM.to_owned());
+
+    Ok(Imported {
+        report: Some(report(&used)),
+        ..Imported::from(ThemeFile::new(name, &extended, &[]))
+    })
+}
+
+struct Used {
+    key: &'static str,
+    usage: Usage,
+    variant: Variant,
+    text: bool,
+}
+
+fn report(used: &[Used]) -> String {
+    let keys: Vec<_> = used
+        .iter()
+        .map(|used| {
+            let part = if used.text { " text" } else { "" };
+
+            format!(
+                "{} → {} {}{part}",
+                used.key,
+                used.usage.label(),
+                used.variant.label()
+            )
+        })
+        .collect();
+
+    let generated: Vec<_> = CELLS
+        .iter()
+        .filter(|cell| {
+            !used
+                .iter()
+                .any(|used| !used.text && used.usage == cell.usage && used.variant == cell.variant)
+        })
+        .map(|cell| format!("{} {}", cell.usage.label(), cell.variant.label()))
+        .collect();
+
+    let mut report = format!("Used {}", keys.join(", "));
+
+    if !generated.is_empty() {
+        report.push_str(&format!("\nGenerated {}", generated.join(", ")));
+    }
+
+    report
+}
+
+/// Blends a possibly translucent color onto an opaque one.
+fn over(color: Color, below: Color) -> Color {
+    let mix = |top: f32, bottom: f32| top * color.a + bottom * (1.0 - color.a);
+
+    Color::from_rgb(
+        mix(color.r, below.r),
+        mix(color.g, below.g),
+        mix(color.b, below.b),
+    )
+}
+
+/// Removes the comments and trailing commas VS Code allows in its JSON.
+fn strip_comments(contents: &str) -> String {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                stripped.push(c);
+
+                while let Some(c) = chars.next() {
+                    stripped.push(c);
+
+                    match c {
+                        '\\' => stripped.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                let _ = chars.next();
+                let mut last = ' ';
+
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '}' | ']' => {
+                let trimmed = stripped.trim_end().len();
+
+                if stripped[..trimmed].ends_with(',') {
+                    stripped.truncate(trimmed - 1);
+                }
+
+                stripped.push(c);
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::contrast;
+    use iced::color;
+
+    const THEME: &str = r##"{
+	// Comments, as VS Code allows them
+	"name": "Sample // not a comment",
+	"type": "hcLight",
+	"colors": {
+		/* The editor */
+		"editor.background": "#fafafa",
+		"editor.foreground": "#383a42",
+		"button.background": "#4078f2",
+		"button.foreground": "#ffffff",
+		"editor.selectionBackground": "#4078f240",
+		"terminal.ansiGreen": "#50a14f",
+		"terminal.ansiRed": "#e45649",
+		"terminal.ansiBrightRed": "#f0f0f0",
+		"sideBar.background": null,
+		"focusBorder": "not a color",
+	},
+}"##;
+
+    fn parsed(contents: &str) -> (Extended, Imported) {
+        let imported = parse(contents).unwrap();
+        (imported.file.extended(), imported)
+    }
+
+    #[test]
+    fn maps_workbench_colors() {
+        let (extended, imported) = parsed(THEME);
+
+        assert_eq!(imported.file.metadata.name, "Sample // not a comment");
+        assert!(!extended.is_dark);
+
+        let background = pair(&extended, Usage::Background, Variant::Base);
+        assert_eq!(background.color, color!(0xfafafa));
+        assert_eq!(background.text, color!(0x383a42));
+
+        let primary = pair(&extended, Usage::Primary, Variant::Base);
+        assert_eq!(primary.color, color!(0x4078f2));
+        assert_eq!(primary.text, Color::WHITE);
+
+        assert_eq!(
+            pair(&extended, Usage::Success, Variant::Base).color,
+            color!(0x50a14f)
+        );
+        assert_eq!(
+            pair(&extended, Usage::Danger, Variant::Base).color,
+            color!(0xe45649)
+        );
+
+        let report = imported.report.unwrap();
+        assert!(report.contains("button.background → Primary Base"));
+        assert!(report.contains("button.foreground → Primary Base text"));
+        assert!(report.contains("Generated Background Weak"));
+    }
+
+    #[test]
+    fn translucent_colors_are_blended() {
+        let (extended, _) = parsed(THEME);
+        let selection = pair(&extended, Usage::Primary, Variant::Weak).color;
+
+        assert_eq!(selection.a, 1.0);
+        assert_eq!(
+            selection.into_rgba8(),
+            over(
+                Color::from_rgba8(0x40, 0x78, 0xf2, 64.0 / 255.0),
+                color!(0xfafafa)
+            )
+            .into_rgba8()
+        );
+    }
+
+    #[test]
+    fn text_stays_readable() {
+        let (extended, _) = parsed(THEME);
+        let strong = pair(&extended, Usage::Danger, Variant::Strong);
+
+        assert_eq!(strong.color, color!(0xf0f0f0));
+        assert!(contrast(strong.color, strong.text) >= 7.0);
+    }
+
+    #[test]
+    fn theme_kinds() {
+        for (kind, is_dark) in [
+            ("vs", false),
+            ("light", false),
+            ("hcLight", false),
+            ("vs-dark", true),
+            ("dark", true),
+            ("hcDark", true),
+            ("hc-black", true),
+        ] {
+            let (extended, _) = parsed(&THEME.replace("hcLight", kind));
+            assert_eq!(extended.is_dark, is_dark, "{kind}");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let missing = THEME.replace("editor.background", "editor.other");
+        assert!(matches!(
+            parse(&missing),
+            Err(Error::Parse(error)) if error == "missing editor.background"
+        ));
+
+        assert!(matches!(parse("{ \"colors\": "), Err(Error::Parse(_))));
+        assert!(matches!(parse("[]"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        assert_eq!(
+            strip_comments("{\"a\": \"/* x */\", // y\n\"b\": [1, 2,], /* z */}"),
+            "{\"a\": \"/* x */\", \n\"b\": [1, 2]}"
+        );
+    }
+}
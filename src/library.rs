@@ -0,0 +1,198 @@
+//! Themes kept between runs, as theme files in the user's config directory.
+use crate::{
+    custom_theme, export,
+    theme_file::{self, Error, ThemeFile},
+};
+use iced::Theme;
+use std::{
+    ffi::OsStr,
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Group {
+    BuiltIn,
+    Mine,
+    Imported,
+}
+
+impl Group {
+    pub const ALL: [Group; 3] = [Group::BuiltIn, Group::Mine, Group::Imported];
+
+    /// The subdirectory of the library the group's themes are kept in.
+    fn directory(self) -> Option<&'static str> {
+        match self {
+            Group::BuiltIn => None,
+            Group::Mine => Some("mine"),
+            Group::Imported => Some("imported"),
+        }
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Group::BuiltIn => "Built-in",
+            Group::Mine => "Mine",
+            Group::Imported => "Imported",
+        })
+    }
+}
+
+/// A theme of the library, which is never a built-in one.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub group: Group,
+    pub path: PathBuf,
+    pub file: ThemeFile,
+}
+
+impl Entry {
+    pub fn theme(&self) -> Theme {
+        custom_theme(self.file.metadata.name.clone(), self.file.extended())
+    }
+}
+
+/// Where the library is kept, if the platform has a config directory.
+pub fn directory() -> Option<PathBuf> {
+    crate::config_directory().map(|config| config.join("themes"))
+}
+
+/// Reads every theme of the library, sorted by group and name. Files which
+/// are not theme files are skipped.
+pub async fn load(directory: PathBuf) -> Result<Vec<Entry>, Error> {
+    let mut entries = Vec::new();
+
+    for group in Group::ALL {
+        let Some(subdirectory) = group.directory() else {
+            continue;
+        };
+
+        let mut files = match tokio::fs::read_dir(directory.join(subdirectory)).await {
+            Ok(files) => files,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        while let Some(file) = files.next_entry().await? {
+            let path = file.path();
+
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+
+            let contents = tokio::fs::read_to_string(&path).await?;
+
+            if let Ok(file) = theme_file::parse(&contents) {
+                entries.push(Entry { group, path, file });
+            }
+        }
+    }
+
+    entries.sort_by_cached_key(|entry| {
+        (
+            entry.group,
+            entry.file.metadata.name.to_lowercase(),
+            entry.path.clone(),
+        )
+    });
+
+    Ok(entries)
+}
+
+/// Writes a new theme into a group, returning the path it was given.
+pub async fn add(directory: PathBuf, group: Group, file: ThemeFile) -> Result<PathBuf, Error> {
+    let Some(subdirectory) = group.directory() else {
+        return Err(Error::Parse(format!("{group} themes cannot be added")));
+    };
+
+    let directory = directory.join(subdirectory);
+    tokio::fs::create_dir_all(&directory).await?;
+
+    let path = available(&directory, &file.metadata.name).await?;
+    theme_file::save(path, file).await
+}
+
+/// Replaces a theme, moving it to a file named after its new name when it
+/// was renamed. A blank name is refused rather than saved as `theme.json`.
+pub async fn save(entry: Entry, file: ThemeFile) -> Result<PathBuf, Error> {
+    let directory = entry.path.parent().unwrap_or(Path::new("."));
+    let name = &file.metadata.name;
+
+    if name.trim().is_empty() {
+        return Err(Error::Parse("the theme needs a name".to_owned()));
+    }
+
+    let path = if export::slug(name) == export::slug(&entry.file.metadata.name) {
+        entry.path.clone()
+    } else {
+        available(directory, name).await?
+    };
+
+    let path = theme_file::save(path, file).await?;
+
+    if path != entry.path {
+        tokio::fs::remove_file(&entry.path).await?;
+    }
+
+    Ok(path)
+}
+
+pub async fn delete(path: PathBuf) -> Result<(), Error> {
+    tokio::fs::remove_file(path).await?;
+
+    Ok(())
+}
+
+/// A path in the directory named after the theme which no file uses yet.
+async fn available(directory: &Path, name: &str) -> Result<PathBuf, Error> {
+    let slug = export::slug(name);
+    let slug = if slug.is_empty() { "theme" } else { &slug };
+
+    for number in 1.. {
+        let path = if number == 1 {
+            directory.join(format!("{slug}.json"))
+        } else {
+            directory.join(format!("{slug}-{number}.json"))
+        };
+
+        if !tokio::fs::try_exists(&path).await? {
+            return Ok(path);
+        }
+    }
+
+    unreachable!("every number was tried")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::futures::executor::block_on;
+
+    #[test]
+    fn blank_names_are_not_saved() {
+        let path = std::env::temp_dir().join(format!(
+            "iced_theme_viewer-{}-blank-name.json",
+            std::process::id()
+        ));
+        let file = ThemeFile::new("Mine".to_owned(), Theme::Nord.extended_palette(), &[]);
+        let entry = Entry {
+            group: Group::Mine,
+            path: path.clone(),
+            file: file.clone(),
+        };
+
+        for name in ["", "   ", "\t\n"] {
+            let mut renamed = file.clone();
+            renamed.metadata.name = name.to_owned();
+
+            assert!(matches!(
+                block_on(save(entry.clone(), renamed)),
+                Err(Error::Parse(_))
+            ));
+        }
+
+        assert!(!path.exists());
+    }
+}
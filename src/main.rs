@@ -1,18 +1,71 @@
 use iced::{
-    Background, Border, Length, Task, Theme,
+    Background, Border, Color, Length, Size, Task, Theme,
     alignment::{Horizontal, Vertical},
     color,
     theme::{
-        self,
+        self, Palette,
         palette::{self, Extended, Pair},
     },
     time::{Duration, Instant},
     widget::{
-        self, Container, Row, TextInput,
+        self, Container, TextInput,
         container::{self},
     },
 };
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{
+    mem,
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+mod broadcast;
+mod browser;
+mod catalogue;
+mod cli;
+mod color;
+mod derivation;
+mod diff;
+mod export;
+mod generate;
+mod import;
+mod library;
+mod nudge;
+mod picker;
+mod popover;
+mod ramp;
+mod search;
+mod session;
+mod sheet;
+mod theme_file;
+mod watch;
+
+use diff::Diff;
+use generate::{Constraints, MinContrast};
+use import::{Imported, terminal};
+use picker::Picker;
+use search::{Search, ThemeId};
+use theme_file::{Metadata, ThemeFile};
+use watch::Watch;
+
+/// The name of custom themes which were not given one.
+const CUSTOM: &str = "Custom";
+
+const LOCK_SIZE: f32 = 16.0;
+const LOCK_SPACING: f32 = 4.0;
+const LIBRARY_WIDTH: f32 = 220.0;
+/// The narrowest the grid is laid out as a table, rather than one row
+/// below the other.
+const GRID_WIDTH: f32 = 760.0;
+/// The size of the swatches of the overview.
+const THUMBNAIL_SWATCH: Size = Size::new(36.0, 18.0);
+/// The widest the grid and the controls below it grow.
+const MAX_WIDTH: f32 = 1100.0;
+const LABEL_WIDTH: f32 = 100.0;
+const VARIANT_WIDTH: f32 = 56.0;
+/// How many changes can be undone.
+const HISTORY: usize = 50;
 
 #[derive(Debug)]
 struct App {
@@ -22,6 +75,66 @@ struct App {
     custom_input: Option<String>,
     last_change: Instant,
     pending: Option<Pending>,
+    locked: Vec<(Usage, Variant)>,
+    seed: String,
+    dark: bool,
+    min_contrast: MinContrast,
+    path: String,
+    status: Option<String>,
+    picker: Option<Picker>,
+    recent: Vec<Color>,
+    copy_format: color::Format,
+    export_format: export::Format,
+    row_menu: Option<Usage>,
+    /// The cell moved to with the arrow keys.
+    selected: Option<(Usage, Variant)>,
+    terminal: Option<terminal::Colors>,
+    ansi_mapping: terminal::Mapping,
+    /// A palette the current one is compared with, and its name.
+    comparison: Option<(String, Extended)>,
+    /// The theme file reloaded whenever it changes.
+    watch: Option<Watch>,
+    broadcast: Option<broadcast::Server>,
+    broadcast_address: String,
+    /// Where user themes are kept, if anywhere.
+    library_directory: Option<PathBuf>,
+    library: Vec<library::Entry>,
+    /// The path of the library theme being viewed.
+    entry: Option<PathBuf>,
+    /// The library theme whose deletion is waiting to be confirmed.
+    deleting: Option<PathBuf>,
+    /// What the custom theme is called and who made it.
+    metadata: Metadata,
+    /// The tags of the custom theme, as they are typed.
+    tags: String,
+    /// Whether the custom theme was opened from a file.
+    imported: bool,
+    /// Whether the library is shown instead of the grid, when the window is
+    /// too narrow for both.
+    show_library: bool,
+    /// The custom themes to go back to, the latest last.
+    undo: Vec<Option<Theme>>,
+    redo: Vec<Option<Theme>>,
+    session_path: Option<PathBuf>,
+    /// The session as it was last saved.
+    saved_session: String,
+    saving_session: bool,
+    /// Whether a message which may have changed the session was handled
+    /// since it was last saved.
+    session_changed: bool,
+    /// The window to close once the session is saved.
+    closing: Option<iced::window::Id>,
+    /// The command palette, while it is open.
+    search: Option<Search>,
+    favorites: Vec<ThemeId>,
+    /// The themes last viewed, the latest first.
+    recent_themes: Vec<ThemeId>,
+    mode: Mode,
+    browser: browser::Browser,
+    show_ramps: bool,
+    show_derivations: bool,
+    /// The variant a step of a tonal ramp is assigned to when clicked.
+    ramp_variant: Variant,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +144,91 @@ enum AppMessage {
     ApplyCustom,
     Tick,
     Action(String, Usage, Variant),
+    Lock(Usage, Variant, bool),
+    Surprise,
+    SeedInput(String),
+    Generate,
+    Dark(bool),
+    MinContrast(MinContrast),
+    Regenerate,
+    PathInput(String),
+    Open,
+    Opened(Result<Box<Imported>, theme_file::Error>),
+    Save,
+    Saved(Result<PathBuf, theme_file::Error>),
+    ExportFormat(export::Format),
+    Export,
+    OpenPicker(Usage, Variant),
+    ClosePicker,
+    Picker(picker::Message),
+    Nudge(Usage, Variant, nudge::Channel, f32),
+    CopyFormat(color::Format),
+    Pasted(Usage, Variant, Option<String>),
+    OpenRowMenu(Usage),
+    CloseRowMenu,
+    CopyRow(Usage),
+    PasteRow(Usage),
+    PastedRow(Usage, Option<String>),
+    AnsiMapping(Usage, terminal::Ansi),
+    Compare,
+    Compared(Result<Box<Imported>, theme_file::Error>),
+    CloseComparison,
+    Watch(bool),
+    CheckWatched,
+    WatchedModified(Option<SystemTime>),
+    Reloaded(Result<Box<Imported>, theme_file::Error>, bool),
+    BroadcastAddress(String),
+    Broadcast(bool),
+    LibraryLoaded(Result<Vec<library::Entry>, theme_file::Error>),
+    SelectEntry(PathBuf),
+    ShowLibrary(bool),
+    AddToLibrary,
+    SaveEntry,
+    DuplicateEntry,
+    DeleteEntry,
+    ConfirmDelete,
+    CancelDelete,
+    /// A change to the library was written, and the theme to select.
+    LibraryChanged(Result<Option<PathBuf>, theme_file::Error>),
+    NameInput(String),
+    AuthorInput(String),
+    DescriptionInput(String),
+    TagsInput(String),
+    Undo,
+    Redo,
+    SessionSaved(Result<(), theme_file::Error>),
+    CloseRequested(iced::window::Id),
+    /// Moves the selected cell, or the highlighted search result, by a
+    /// number of rows and columns.
+    Move(isize, isize),
+    Edit,
+    Cancel,
+    /// Selects the theme a number of places after the current one.
+    CycleTheme(isize),
+    OpenSearch,
+    CloseSearch,
+    SearchInput(String),
+    /// Opens the highlighted search result.
+    SearchSubmit,
+    OpenTheme(ThemeId),
+    ToggleFavorite(ThemeId),
+    /// A library theme was saved, under the path it had before or a new one.
+    EntrySaved(PathBuf, Result<PathBuf, theme_file::Error>),
+    Mode(Mode),
+    Browser(browser::Message),
+    ShowRamps(bool),
+    ShowDerivations(bool),
+    RampVariant(Variant),
+    RampStep(Usage, Color),
+}
+
+/// What the window shows next to the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Mode {
+    #[default]
+    Editor,
+    Browser,
+    Overview,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -39,14 +237,28 @@ struct Pending {
     variant: Variant,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum Variant {
     Base,
     Weak,
     Strong,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+impl Variant {
+    const ALL: [Variant; 3] = [Variant::Base, Variant::Weak, Variant::Strong];
+
+    fn label(self) -> &'static str {
+        match self {
+            Variant::Base => "Base",
+            Variant::Weak => "Weak",
+            Variant::Strong => "Strong",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum Usage {
     Primary,
     Secondary,
@@ -55,6 +267,32 @@ enum Usage {
     Danger,
 }
 
+impl Usage {
+    /// All usages, in the order they are displayed.
+    const ALL: [Usage; 5] = [
+        Usage::Background,
+        Usage::Primary,
+        Usage::Secondary,
+        Usage::Success,
+        Usage::Danger,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Usage::Primary => "Primary",
+            Usage::Secondary => "Secondary",
+            Usage::Background => "Background",
+            Usage::Success => "Success",
+            Usage::Danger => "Danger",
+        }
+    }
+}
+
+/// Where the viewer keeps its files, if the platform has a config directory.
+fn config_directory() -> Option<PathBuf> {
+    dirs::config_dir().map(|config| config.join("iced_theme_viewer"))
+}
+
 fn all_themes() -> Vec<Theme> {
     vec![
         Theme::Light,
@@ -96,10 +334,6 @@ fn _square<'a>(text: &'a str, colors: Pair) -> Container<'a, AppMessage> {
         })
 }
 
-fn my_text<'a, M>(text: &'a str) -> Container<'a, M> {
-    widget::container(widget::text(text)).center_y(64.0)
-}
-
 impl App {
     pub fn new() -> Self {
         Self {
@@ -109,21 +343,227 @@ impl App {
             custom_input: None,
             pending: None,
             last_change: Instant::now(),
+            locked: Vec::new(),
+            seed: String::new(),
+            dark: false,
+            min_contrast: MinContrast::default(),
+            path: String::new(),
+            status: None,
+            picker: None,
+            recent: Vec::new(),
+            copy_format: color::Format::default(),
+            export_format: export::Format::default(),
+            row_menu: None,
+            selected: None,
+            terminal: None,
+            ansi_mapping: terminal::Mapping::default(),
+            comparison: None,
+            watch: None,
+            broadcast: None,
+            broadcast_address: iced_theme_client::DEFAULT_ADDRESS.to_owned(),
+            library_directory: library::directory(),
+            library: Vec::new(),
+            entry: None,
+            deleting: None,
+            metadata: Metadata::default(),
+            tags: String::new(),
+            imported: false,
+            show_library: false,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            session_path: session::path(),
+            saved_session: String::new(),
+            saving_session: false,
+            session_changed: false,
+            closing: None,
+            search: None,
+            favorites: Vec::new(),
+            recent_themes: Vec::new(),
+            mode: Mode::default(),
+            browser: browser::Browser::default(),
+            show_ramps: false,
+            show_derivations: false,
+            ramp_variant: Variant::Weak,
+        }
+    }
+
+    /// Restores the state saved by the last run of the viewer. A fresh start
+    /// only keeps the favorite and recently viewed themes.
+    fn restore(&mut self, fresh: bool) {
+        let Some(path) = &self.session_path else {
+            return;
+        };
+
+        let session = match session::load(path) {
+            Ok(session) => session,
+            Err(error) => {
+                self.status = Some(format!("Could not restore the last session: {error}"));
+                return;
+            }
+        };
+
+        self.favorites = session.favorites;
+        self.recent_themes = session.recent_themes;
+
+        if fresh {
+            return;
+        }
+
+        if let Some(theme) = session
+            .theme
+            .and_then(|name| self.themes.iter().find(|theme| theme.to_string() == name))
+        {
+            self.theme = theme.clone();
+        }
+
+        fn theme(file: Option<ThemeFile>) -> Option<Theme> {
+            file.map(|file| custom_theme(file.metadata.name.clone(), file.extended()))
+        }
+
+        if let Some(file) = &session.custom {
+            self.set_metadata(file.metadata.clone());
+        }
+
+        self.custom = theme(session.custom);
+        // Checked again against the library once it is loaded
+        self.entry = session.entry.filter(|path| {
+            self.library_directory
+                .as_ref()
+                .is_some_and(|directory| path.starts_with(directory))
+        });
+        self.locked = session.locked;
+        self.undo = session.undo.into_iter().map(theme).collect();
+        self.redo = session.redo.into_iter().map(theme).collect();
+        self.dark = self.current().extended_palette().is_dark;
+
+        if let Some(pending) = session.pending {
+            self.pending = Some(Pending {
+                usage: pending.usage,
+                variant: pending.variant,
+            });
+            self.custom_input = Some(pending.input);
+        }
+    }
+
+    fn session(&self) -> session::Session {
+        let file = |theme: &Option<Theme>| {
+            theme
+                .as_ref()
+                .map(|theme| ThemeFile::new(theme.to_string(), theme.extended_palette(), &[]))
+        };
+
+        session::Session {
+            theme: Some(self.theme.to_string()),
+            entry: self.entry.clone(),
+            custom: self.custom.as_ref().map(|_| self.file()),
+            locked: self.locked.clone(),
+            pending: self.pending.zip(self.custom_input.clone()).map(
+                |(Pending { usage, variant }, input)| session::Pending {
+                    usage,
+                    variant,
+                    input,
+                },
+            ),
+            undo: self.undo.iter().map(file).collect(),
+            redo: self.redo.iter().map(file).collect(),
+            favorites: self.favorites.clone(),
+            recent_themes: self.recent_themes.clone(),
+        }
+    }
+
+    /// Saves the session if it changed, unless it is already being saved.
+    /// Called once a second, on [`AppMessage::Tick`].
+    fn save_session(&mut self) -> Task<AppMessage> {
+        let Some(path) = self.session_path.clone() else {
+            return Task::none();
+        };
+
+        if self.saving_session || !self.session_changed {
+            return Task::none();
+        }
+
+        self.session_changed = false;
+
+        let contents = match session::to_json(&self.session()) {
+            Ok(contents) if contents != self.saved_session => contents,
+            _ => return Task::none(),
+        };
+
+        self.saving_session = true;
+        self.saved_session.clone_from(&contents);
+
+        Task::perform(session::save(path, contents), AppMessage::SessionSaved)
+    }
+
+    fn load_library(&self) -> Task<AppMessage> {
+        match &self.library_directory {
+            Some(directory) => {
+                Task::perform(library::load(directory.clone()), AppMessage::LibraryLoaded)
+            }
+            None => Task::none(),
         }
     }
 
     pub fn update(&mut self, message: AppMessage) -> Task<AppMessage> {
+        let before = self.broadcast.is_some().then(|| self.current().clone());
+        let broadcast = matches!(message, AppMessage::Broadcast(true));
+        let tick = matches!(message, AppMessage::Tick);
+        self.session_changed |= changes_session(&message);
+
+        let task = self.handle(message);
+
+        // Saving waits for the next tick, so that a burst of changes, such
+        // as dragging a slider or typing, is serialized only once
+        let save = if tick {
+            self.save_session()
+        } else {
+            Task::none()
+        };
+
+        // Only a theme which changed is sent, or the current one to a new
+        // server
+        if let Some(server) = &self.broadcast {
+            let theme = self.current();
+
+            if broadcast || before.as_ref() != Some(theme) {
+                server.publish(ThemeFile::new(
+                    theme.to_string(),
+                    theme.extended_palette(),
+                    &[],
+                ));
+            }
+        }
+
+        Task::batch([task, save])
+    }
+
+    fn handle(&mut self, message: AppMessage) -> Task<AppMessage> {
         match message {
             AppMessage::Select(theme) => {
+                self.picker = None;
                 self.pending = None;
                 self.custom_input = None;
                 self.custom = None;
+                self.terminal = None;
+                self.watch = None;
+                self.entry = None;
+                self.imported = false;
+                self.show_library = false;
+                self.undo.clear();
+                self.redo.clear();
+                self.dark = theme.extended_palette().is_dark;
+                self.viewed(ThemeId::BuiltIn(theme.to_string()));
                 self.theme = theme;
             }
             AppMessage::ResetCustom => {
+                self.record();
                 self.pending = None;
                 self.custom_input = None;
                 self.custom = None;
+                self.terminal = None;
+                self.entry = None;
+                self.imported = false;
+                self.status = None;
             }
             AppMessage::Tick => {
                 if self.last_change.elapsed() >= Duration::from_millis(750) {
@@ -141,6 +581,659 @@ impl App {
                 self.custom_input = Some(input);
 
                 self.pending = Some(Pending { usage, variant });
+                self.selected = Some((usage, variant));
+            }
+            AppMessage::Lock(usage, variant, locked) => {
+                self.locked.retain(|&cell| cell != (usage, variant));
+
+                if locked {
+                    self.locked.push((usage, variant));
+                }
+            }
+            AppMessage::Surprise => {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+
+                self.seed = seed.to_string();
+                self.generate(seed);
+            }
+            AppMessage::SeedInput(seed) => {
+                self.seed = seed;
+            }
+            AppMessage::Generate => match self.seed.trim().parse::<u64>() {
+                Ok(seed) => self.generate(seed),
+                Err(_) => self.status = Some("Seed must be a whole number".to_owned()),
+            },
+            AppMessage::Dark(dark) => {
+                self.dark = dark;
+            }
+            AppMessage::MinContrast(min_contrast) => {
+                self.min_contrast = min_contrast;
+            }
+            AppMessage::AnsiMapping(usage, ansi) => {
+                self.ansi_mapping.set(usage, ansi);
+
+                if let Some(colors) = &self.terminal {
+                    let current = *self.current().extended_palette();
+                    let mapped = colors.extended(self.ansi_mapping);
+
+                    self.set_custom(with_locked(mapped, &current, &self.locked));
+                }
+            }
+            AppMessage::Regenerate => {
+                self.pending = None;
+                self.custom_input = None;
+
+                let current = *self.current().extended_palette();
+                let generated = Extended::generate(base_palette(&current));
+
+                self.set_custom(with_locked(generated, &current, &self.locked));
+            }
+            AppMessage::PathInput(path) => {
+                self.path = path;
+            }
+            AppMessage::Open => {
+                let path = PathBuf::from(self.path.trim());
+                return Task::perform(import::load(path), |result| {
+                    AppMessage::Opened(result.map(Box::new))
+                });
+            }
+            AppMessage::Opened(Ok(imported)) => {
+                let Imported {
+                    file,
+                    report,
+                    terminal,
+                } = *imported;
+
+                let extended = terminal.as_ref().map_or_else(
+                    || file.extended(),
+                    |colors| colors.extended(self.ansi_mapping),
+                );
+
+                self.pending = None;
+                self.custom_input = None;
+                self.locked = file.locked.clone();
+                self.custom = Some(custom_theme(file.metadata.name.clone(), extended));
+                self.set_metadata(file.metadata);
+                self.terminal = terminal;
+                self.entry = None;
+                self.imported = true;
+                self.undo.clear();
+                self.redo.clear();
+                self.status = report;
+            }
+            AppMessage::Opened(Err(error)) => {
+                self.status = Some(format!("Could not open theme: {error}"));
+            }
+            AppMessage::Compare => {
+                let path = PathBuf::from(self.path.trim());
+                return Task::perform(import::load(path), |result| {
+                    AppMessage::Compared(result.map(Box::new))
+                });
+            }
+            AppMessage::Compared(Ok(imported)) => {
+                let Imported { file, terminal, .. } = *imported;
+
+                let extended = terminal.as_ref().map_or_else(
+                    || file.extended(),
+                    |colors| colors.extended(self.ansi_mapping),
+                );
+
+                self.comparison = Some((file.metadata.name, extended));
+            }
+            AppMessage::Compared(Err(error)) => {
+                self.status = Some(format!("Could not open theme: {error}"));
+            }
+            AppMessage::CloseComparison => {
+                self.comparison = None;
+            }
+            AppMessage::Watch(watch) => {
+                self.watch = watch.then(|| Watch::new(PathBuf::from(self.path.trim())));
+
+                if watch {
+                    return Task::done(AppMessage::CheckWatched);
+                }
+            }
+            AppMessage::BroadcastAddress(address) => {
+                self.broadcast_address = address;
+            }
+            AppMessage::Broadcast(false) => {
+                self.broadcast = None;
+            }
+            AppMessage::Broadcast(true) => {
+                let server = self
+                    .broadcast_address
+                    .parse()
+                    .and_then(broadcast::Server::start);
+
+                match server {
+                    Ok(server) => {
+                        self.broadcast = Some(server);
+                        self.status = None;
+                    }
+                    Err(error) => {
+                        self.status = Some(format!(
+                            "Could not broadcast on {}: {error}",
+                            self.broadcast_address.trim()
+                        ));
+                    }
+                }
+            }
+            AppMessage::LibraryLoaded(Ok(library)) => {
+                self.library = library;
+
+                // A theme deleted or moved since the session was saved
+                if self.entry.is_some() && self.selected_entry().is_none() {
+                    self.entry = None;
+                }
+            }
+            AppMessage::LibraryLoaded(Err(error)) => {
+                self.status = Some(format!("Could not read the theme library: {error}"));
+            }
+            AppMessage::SelectEntry(path) => {
+                let Some(entry) = self.library.iter().find(|entry| entry.path == path) else {
+                    return Task::none();
+                };
+
+                self.picker = None;
+                self.pending = None;
+                self.custom_input = None;
+                self.terminal = None;
+                self.watch = None;
+                self.imported = false;
+                self.show_library = false;
+                self.locked = entry.file.locked.clone();
+                self.dark = entry.file.is_dark;
+                self.custom = Some(entry.theme());
+                self.set_metadata(entry.file.metadata.clone());
+
+                if self.entry.as_ref() != Some(&path) {
+                    self.undo.clear();
+                    self.redo.clear();
+                }
+
+                self.viewed(ThemeId::Entry(path.clone()));
+                self.entry = Some(path);
+            }
+            AppMessage::ShowLibrary(show) => {
+                self.show_library = show;
+            }
+            AppMessage::NameInput(name) => {
+                if let Some(custom) = &self.custom {
+                    // The theme keeps its last name until a new one is typed
+                    if !name.trim().is_empty() {
+                        self.custom = Some(custom_theme(name.clone(), *custom.extended_palette()));
+                    }
+
+                    self.metadata.name = name;
+                }
+            }
+            AppMessage::AuthorInput(author) => {
+                self.metadata.author = author;
+            }
+            AppMessage::DescriptionInput(description) => {
+                self.metadata.description = description;
+            }
+            AppMessage::TagsInput(tags) => {
+                self.metadata.tags = Metadata::parse_tags(&tags);
+                self.tags = tags;
+            }
+            AppMessage::AddToLibrary => {
+                let Some(directory) = self.library_directory.clone() else {
+                    return Task::none();
+                };
+
+                if !self.is_named() {
+                    self.status = Some("The theme needs a name".to_owned());
+                    return Task::none();
+                }
+
+                let group = if self.imported {
+                    library::Group::Imported
+                } else {
+                    library::Group::Mine
+                };
+
+                return Task::perform(library::add(directory, group, self.file()), |result| {
+                    AppMessage::LibraryChanged(result.map(Some))
+                });
+            }
+            AppMessage::SaveEntry => {
+                let Some(entry) = self.selected_entry() else {
+                    return Task::none();
+                };
+
+                if !self.is_named() {
+                    self.status = Some("The theme needs a name".to_owned());
+                    return Task::none();
+                }
+
+                let previous = entry.path.clone();
+
+                return Task::perform(library::save(entry.clone(), self.file()), move |result| {
+                    AppMessage::EntrySaved(previous.clone(), result)
+                });
+            }
+            AppMessage::EntrySaved(previous, result) => {
+                if let Ok(path) = &result {
+                    let previous = ThemeId::Entry(previous);
+
+                    for id in self.favorites.iter_mut().chain(&mut self.recent_themes) {
+                        if *id == previous {
+                            *id = ThemeId::Entry(path.clone());
+                        }
+                    }
+                }
+
+                return self.handle(AppMessage::LibraryChanged(result.map(Some)));
+            }
+            AppMessage::DuplicateEntry => {
+                let Some((directory, entry)) =
+                    self.library_directory.clone().zip(self.selected_entry())
+                else {
+                    return Task::none();
+                };
+
+                let file = ThemeFile {
+                    metadata: Metadata {
+                        name: format!("{} copy", entry.file.metadata.name),
+                        ..entry.file.metadata.clone()
+                    },
+                    ..entry.file.clone()
+                };
+
+                return Task::perform(library::add(directory, entry.group, file), |result| {
+                    AppMessage::LibraryChanged(result.map(Some))
+                });
+            }
+            AppMessage::DeleteEntry => {
+                self.deleting = self.entry.clone();
+            }
+            AppMessage::CancelDelete => {
+                self.deleting = None;
+            }
+            AppMessage::ConfirmDelete => {
+                let Some(path) = self
+                    .deleting
+                    .take()
+                    .filter(|path| self.entry.as_ref() == Some(path))
+                else {
+                    return Task::none();
+                };
+
+                self.entry = None;
+
+                let id = ThemeId::Entry(path.clone());
+                self.favorites.retain(|favorite| *favorite != id);
+                self.recent_themes.retain(|recent| *recent != id);
+
+                self.pending = None;
+                self.custom_input = None;
+                self.custom = None;
+
+                return Task::perform(library::delete(path), |result| {
+                    AppMessage::LibraryChanged(result.map(|()| None))
+                });
+            }
+            AppMessage::LibraryChanged(Ok(selected)) => {
+                let load = self.load_library();
+
+                let Some(path) = selected else {
+                    return load;
+                };
+
+                return load.chain(Task::done(AppMessage::SelectEntry(path)));
+            }
+            AppMessage::LibraryChanged(Err(error)) => {
+                self.status = Some(format!("Could not update the theme library: {error}"));
+                return self.load_library();
+            }
+            AppMessage::Undo => {
+                if let Some(custom) = self.undo.pop() {
+                    self.pending = None;
+                    self.custom_input = None;
+
+                    let custom = self.renamed(custom);
+                    self.redo.push(mem::replace(&mut self.custom, custom));
+                }
+            }
+            AppMessage::Redo => {
+                if let Some(custom) = self.redo.pop() {
+                    self.pending = None;
+                    self.custom_input = None;
+
+                    let custom = self.renamed(custom);
+                    self.undo.push(mem::replace(&mut self.custom, custom));
+                }
+            }
+            AppMessage::SessionSaved(result) => {
+                self.saving_session = false;
+
+                if let Err(error) = result {
+                    self.status = Some(format!("Could not save the session: {error}"));
+                }
+
+                if let Some(id) = self.closing {
+                    return self.close(id);
+                }
+            }
+            AppMessage::CloseRequested(id) => {
+                // The session is saved once the save in progress is done
+                if self.saving_session {
+                    self.closing = Some(id);
+                } else {
+                    return self.close(id);
+                }
+            }
+            AppMessage::CheckWatched => {
+                if let Some(watch) = &self.watch {
+                    return Task::perform(
+                        watch::modified(watch.path.clone()),
+                        AppMessage::WatchedModified,
+                    );
+                }
+            }
+            AppMessage::WatchedModified(modified) => {
+                let Some(watch) = &mut self.watch else {
+                    return Task::none();
+                };
+
+                match modified {
+                    Some(modified) if watch.update(modified) => {
+                        return Task::perform(import::load(watch.path.clone()), |result| {
+                            AppMessage::Reloaded(result.map(Box::new), false)
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.status = Some(format!("Cannot read {}", watch.path.display()));
+                    }
+                }
+            }
+            AppMessage::Reloaded(result, retried) => {
+                let Some(watch) = &self.watch else {
+                    return Task::none();
+                };
+
+                match result {
+                    Ok(imported) => return self.handle(AppMessage::Opened(Ok(imported))),
+                    Err(_) if !retried => {
+                        return Task::perform(watch::retry(watch.path.clone()), |result| {
+                            AppMessage::Reloaded(result.map(Box::new), true)
+                        });
+                    }
+                    // The theme loaded last stays applied
+                    Err(error) => {
+                        self.status = Some(format!(
+                            "Could not reload {}: {error}",
+                            watch.path.display()
+                        ));
+                    }
+                }
+            }
+            AppMessage::Save => {
+                let path = PathBuf::from(self.path.trim());
+                return Task::perform(theme_file::save(path, self.file()), AppMessage::Saved);
+            }
+            AppMessage::ExportFormat(format) => {
+                self.export_format = format;
+            }
+            AppMessage::Export => {
+                let mut path = PathBuf::from(self.path.trim());
+
+                if path.extension().is_none() {
+                    let _ = path.set_extension(self.export_format.extension());
+                }
+
+                let contents = self
+                    .export_format
+                    .export(&self.file().metadata, self.current().extended_palette());
+                return Task::perform(export::save(path, contents), AppMessage::Saved);
+            }
+            AppMessage::Saved(Ok(path)) => {
+                self.status = Some(format!("Saved to {}", path.display()));
+            }
+            AppMessage::Saved(Err(error)) => {
+                self.status = Some(format!("Could not save theme: {error}"));
+            }
+            AppMessage::OpenPicker(usage, variant) => {
+                let task = self.close_picker();
+
+                let same_cell = self
+                    .picker
+                    .take()
+                    .is_some_and(|picker| picker.usage == usage && picker.variant == variant);
+
+                if !same_cell {
+                    let color = get_pair(self.current(), usage, variant).color;
+                    self.picker = Some(Picker::new(usage, variant, color));
+                }
+
+                return task;
+            }
+            AppMessage::ClosePicker => {
+                let task = self.close_picker();
+                self.picker = None;
+
+                return task;
+            }
+            AppMessage::Picker(picker::Message::Copy) => {
+                if let Some(picker) = &self.picker {
+                    return self.copy(self.copy_format.format(picker.color()));
+                }
+            }
+            AppMessage::Picker(picker::Message::Paste) => {
+                if let Some(Picker { usage, variant, .. }) = self.picker {
+                    return iced::clipboard::read()
+                        .map(move |contents| AppMessage::Pasted(usage, variant, contents));
+                }
+            }
+            AppMessage::Picker(message) => {
+                let Some(picker) = self.picker.as_mut() else {
+                    return Task::none();
+                };
+
+                if let Some(color) = picker.update(message) {
+                    let input = color::to_hex(color);
+                    return Task::done(AppMessage::Action(input, picker.usage, picker.variant));
+                }
+            }
+            AppMessage::Nudge(usage, variant, channel, steps) => {
+                let color = get_pair(self.current(), usage, variant).color;
+                self.set_colors(usage, &[(variant, nudge::apply(color, channel, steps))]);
+            }
+            AppMessage::CopyFormat(format) => {
+                self.copy_format = format;
+            }
+            AppMessage::Pasted(usage, variant, contents) => {
+                match contents.as_deref().and_then(color::parse) {
+                    Some(color) => {
+                        self.set_colors(usage, &[(variant, color)]);
+
+                        if let Some(picker) = self
+                            .picker
+                            .as_mut()
+                            .filter(|picker| picker.usage == usage && picker.variant == variant)
+                        {
+                            let _ = picker.update(picker::Message::Recent(color));
+                        }
+                    }
+                    None => self.status = Some("The clipboard does not contain a color".to_owned()),
+                }
+            }
+            AppMessage::OpenRowMenu(usage) => {
+                self.row_menu = (self.row_menu != Some(usage)).then_some(usage);
+            }
+            AppMessage::CloseRowMenu => {
+                self.row_menu = None;
+            }
+            // The grid and the theme stay as they are behind the palette
+            AppMessage::CycleTheme(_) | AppMessage::Edit if self.search.is_some() => {}
+            AppMessage::Move(rows, _) if self.search.is_some() => {
+                let count = self
+                    .search
+                    .as_ref()
+                    .map_or(0, |search| self.search_results(search).len() as isize);
+
+                let Some(search) = &mut self.search else {
+                    return Task::none();
+                };
+
+                search.selected = (search.selected as isize + rows).min(count - 1).max(0) as usize;
+
+                // Results are about the same height, which is close enough
+                // to keep the highlighted one in view
+                let offset = search.selected as f32 / (count - 1).max(1) as f32;
+
+                return widget::scrollable::snap_to(
+                    results_id(),
+                    widget::scrollable::RelativeOffset { x: 0.0, y: offset },
+                );
+            }
+            AppMessage::Move(rows, columns) => {
+                self.selected = Some(match self.selected {
+                    Some((usage, variant)) => (
+                        offset(&Usage::ALL, usage, rows),
+                        offset(&Variant::ALL, variant, columns),
+                    ),
+                    None => (Usage::Background, Variant::Base),
+                });
+            }
+            AppMessage::Edit => {
+                let (usage, variant) = *self
+                    .selected
+                    .get_or_insert((Usage::Background, Variant::Base));
+                let id = input_id(usage, variant);
+
+                return widget::text_input::focus(id.clone())
+                    .chain(widget::text_input::select_all(id));
+            }
+            AppMessage::Cancel => {
+                self.deleting = None;
+                self.search = None;
+                self.picker = None;
+                self.row_menu = None;
+                self.pending = None;
+                self.custom_input = None;
+            }
+            AppMessage::CycleTheme(step) => {
+                let count = self.themes.len() as isize;
+                let index = self
+                    .themes
+                    .iter()
+                    .position(|theme| *theme == self.theme)
+                    .map_or(0, |index| index as isize);
+
+                let theme = self.themes[(index + step).rem_euclid(count) as usize].clone();
+                return self.handle(AppMessage::Select(theme));
+            }
+            AppMessage::OpenSearch => {
+                self.search = Some(Search::default());
+
+                return widget::text_input::focus(search_id());
+            }
+            AppMessage::CloseSearch => {
+                self.search = None;
+            }
+            AppMessage::SearchInput(query) => {
+                self.search = Some(Search { query, selected: 0 });
+
+                return widget::scrollable::snap_to(
+                    results_id(),
+                    widget::scrollable::RelativeOffset::START,
+                );
+            }
+            AppMessage::SearchSubmit => {
+                let Some(search) = &self.search else {
+                    return Task::none();
+                };
+
+                if let Some((_, result)) = self.search_results(search).get(search.selected) {
+                    return self.handle(AppMessage::OpenTheme(result.id.clone()));
+                }
+            }
+            AppMessage::OpenTheme(id) => {
+                self.search = None;
+
+                match id {
+                    ThemeId::BuiltIn(name) => {
+                        if let Some(theme) =
+                            self.themes.iter().find(|theme| theme.to_string() == name)
+                        {
+                            return self.handle(AppMessage::Select(theme.clone()));
+                        }
+                    }
+                    ThemeId::Entry(path) => return self.handle(AppMessage::SelectEntry(path)),
+                }
+            }
+            AppMessage::ShowRamps(show) => {
+                self.show_ramps = show;
+            }
+            AppMessage::ShowDerivations(show) => {
+                self.show_derivations = show;
+            }
+            AppMessage::RampVariant(variant) => {
+                self.ramp_variant = variant;
+            }
+            AppMessage::RampStep(usage, color) => {
+                self.set_colors(usage, &[(self.ramp_variant, color)]);
+            }
+            AppMessage::Mode(mode) => {
+                self.mode = mode;
+            }
+            AppMessage::Browser(message) => {
+                if let Some(id) = self.browser.update(message) {
+                    return self.handle(AppMessage::OpenTheme(id));
+                }
+            }
+            AppMessage::ToggleFavorite(id) => {
+                if self.favorites.contains(&id) {
+                    self.favorites.retain(|favorite| *favorite != id);
+                } else {
+                    self.favorites.push(id);
+                }
+            }
+            AppMessage::CopyRow(usage) => {
+                self.row_menu = None;
+
+                let row = Variant::ALL
+                    .into_iter()
+                    .map(|variant| {
+                        let color = get_pair(self.current(), usage, variant).color;
+                        self.copy_format.format(color)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                return self.copy(row);
+            }
+            AppMessage::PasteRow(usage) => {
+                self.row_menu = None;
+
+                return iced::clipboard::read()
+                    .map(move |contents| AppMessage::PastedRow(usage, contents));
+            }
+            AppMessage::PastedRow(usage, contents) => {
+                let colors = contents
+                    .iter()
+                    .flat_map(|contents| contents.lines())
+                    .filter(|line| !line.trim().is_empty())
+                    .map(color::parse)
+                    .take(Variant::ALL.len())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|colors| colors.len() == Variant::ALL.len());
+
+                match colors {
+                    Some(colors) => {
+                        let colors: Vec<_> = Variant::ALL.into_iter().zip(colors).collect();
+                        self.set_colors(usage, &colors);
+                    }
+                    None => {
+                        self.status =
+                            Some("The clipboard does not contain a row of three colors".to_owned());
+                    }
+                }
             }
         }
 
@@ -168,466 +1261,1235 @@ impl App {
 
         let ext = updated_extended(ext, pair, usage, variant);
 
-        let custom =
-            theme::Custom::with_fn("Custom".to_owned(), theme::Palette::DARK, move |_| ext);
+        self.set_custom(ext);
+    }
+
+    /// Replaces the custom theme, keeping its metadata. Editing a built-in
+    /// theme names the custom one after it.
+    fn set_custom(&mut self, extended: Extended) {
+        self.record();
 
-        let custom = Theme::Custom(Arc::new(custom));
+        if self.custom.is_none() {
+            self.set_metadata(Metadata::new(format!("{} {CUSTOM}", self.theme)));
+        }
 
-        self.custom = Some(custom);
+        self.custom = Some(custom_theme(self.metadata.name.clone(), extended));
     }
 
-    fn subscription(&self) -> iced::Subscription<AppMessage> {
-        iced::time::every(Duration::from_secs(1)).map(|_| AppMessage::Tick)
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.tags = metadata.tags.join(", ");
+        self.metadata = metadata;
     }
 
-    pub fn view(&self) -> iced::Element<'_, AppMessage> {
-        let theme = self.custom.as_ref().unwrap_or(&self.theme);
+    /// A custom theme from the undo history, named as the theme is now.
+    fn renamed(&self, custom: Option<Theme>) -> Option<Theme> {
+        custom.map(|theme| custom_theme(self.metadata.name.clone(), *theme.extended_palette()))
+    }
 
-        let header = widget::text("Default Themes")
-            .size(32.0)
-            .center()
-            .width(Length::Fill);
+    /// Whether the theme has a name to be stored under. Only custom themes
+    /// can be renamed.
+    fn is_named(&self) -> bool {
+        self.custom.is_none() || !self.metadata.name.trim().is_empty()
+    }
 
-        let theme_selector = widget::container(widget::pick_list(
-            self.themes.clone(),
-            Some(self.theme.clone()),
-            AppMessage::Select,
-        ))
-        .center_x(Length::Fill);
+    /// The current theme, as it is saved.
+    fn file(&self) -> ThemeFile {
+        let theme = self.current();
 
-        let spacing = 16.0;
+        let metadata = if self.custom.is_some() {
+            self.metadata.clone()
+        } else {
+            Metadata::new(theme.to_string())
+        };
 
-        let labels = widget::row!(
-            widget::horizontal_space().width(155),
-            my_text("Base"),
-            widget::horizontal_space().width(145),
-            my_text("Weak"),
-            widget::horizontal_space().width(135),
-            my_text("Strong"),
-            widget::horizontal_space().width(155),
-        )
-        .align_y(Vertical::Center)
-        .spacing(0);
+        ThemeFile {
+            metadata,
+            ..ThemeFile::new(String::new(), theme.extended_palette(), &self.locked)
+        }
+    }
 
-        let background_row = self
-            .background(theme)
-            .spacing(spacing)
-            .align_y(Vertical::Center);
+    fn generate(&mut self, seed: u64) {
+        self.pending = None;
+        self.custom_input = None;
 
-        let primary_row = self
-            .primary(theme)
-            .spacing(spacing)
-            .align_y(Vertical::Center);
+        let current = *self.current().extended_palette();
 
-        let secondary_row = self
-            .secondary(theme)
-            .spacing(spacing)
-            .align_y(Vertical::Center);
+        let constraints = Constraints {
+            min_contrast: self.min_contrast,
+            is_dark: self.dark,
+            locked: self.locked.clone(),
+        };
 
-        let success_row = self
-            .success(theme)
-            .spacing(spacing)
-            .align_y(Vertical::Center);
+        match generate::generate(seed, &constraints, &current) {
+            Some(ext) => {
+                if self.custom.is_none() {
+                    self.set_metadata(Metadata::new(CUSTOM.to_owned()));
+                }
 
-        let danger_row = self
-            .danger(theme)
-            .spacing(spacing)
-            .align_y(Vertical::Center);
+                self.record();
+                self.custom = Some(custom_theme(self.metadata.name.clone(), ext));
+                self.terminal = None;
+                self.status = None;
+            }
+            None => {
+                self.status = Some(format!(
+                    "No palette satisfies the constraints for seed {seed}"
+                ));
+            }
+        }
+    }
 
-        let colors = widget::column!(
-            background_row,
-            primary_row,
-            secondary_row,
-            success_row,
-            danger_row
-        )
-        .spacing(spacing);
+    /// Replaces the colors of some cells right away. Their text colors are
+    /// kept while they reach the 7:1 contrast iced's generator asks for, and
+    /// replaced with white or black otherwise, as [`Pair::new`] does.
+    fn set_colors(&mut self, usage: Usage, colors: &[(Variant, Color)]) {
+        if self.pending.is_some_and(|pending| pending.usage == usage) {
+            self.pending = None;
+            self.custom_input = None;
+        }
 
-        let content = widget::column!(labels, colors);
+        let ext = colors.iter().fold(
+            *self.current().extended_palette(),
+            |ext, &(variant, color)| {
+                let text = pair(&ext, usage, variant).text;
+                updated_extended(ext, Pair::new(color, text), usage, variant)
+            },
+        );
 
-        let btn_message = if self.custom.is_some() {
-            Some(AppMessage::ResetCustom)
-        } else {
-            None
-        };
-        let reset = widget::button("Reset Custom").on_press_maybe(btn_message);
+        self.set_custom(ext);
+    }
 
-        widget::column![
-            header,
-            theme_selector,
-            content,
-            widget::vertical_space().height(25.0),
-            reset
-        ]
-        .align_x(Horizontal::Center)
-        .spacing(spacing)
-        .padding(16.0)
-        .into()
+    fn copy(&mut self, contents: String) -> Task<AppMessage> {
+        self.status = Some(format!("Copied {}", contents.replace('\n', ", ")));
+        iced::clipboard::write(contents)
     }
 
-    fn background(&self, theme: &Theme) -> Row<'_, AppMessage> {
-        let usage = Usage::Background;
-
-        let base = {
-            let variant = Variant::Base;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+    /// Remembers the color of the open picker, if any, and applies it right away.
+    fn close_picker(&mut self) -> Task<AppMessage> {
+        let Some(picker) = self.picker.as_ref().filter(|picker| picker.changed()) else {
+            return Task::none();
+        };
 
-            let content = widget::text_input("rgb or hex", &value);
+        let color = picker.color();
 
-            text_input(content, usage, variant)
-        };
+        self.recent.retain(|&recent| recent != color);
+        self.recent.insert(0, color);
+        self.recent.truncate(picker::RECENT);
 
-        let weak = {
-            let variant = Variant::Weak;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        Task::done(AppMessage::ApplyCustom)
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    /// Remembers the custom theme, so that the change about to be made to
+    /// it can be undone.
+    fn record(&mut self) {
+        self.undo.push(self.custom.clone());
+        self.redo.clear();
 
-            text_input(content, usage, variant)
-        };
+        if self.undo.len() > HISTORY {
+            let _ = self.undo.remove(0);
+        }
+    }
 
-        let strong = {
-            let variant = Variant::Strong;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+    /// Saves the session one last time, then closes the window.
+    fn close(&mut self, id: iced::window::Id) -> Task<AppMessage> {
+        match (&self.session_path, session::to_json(&self.session())) {
+            (Some(path), Ok(contents)) => {
+                Task::perform(session::save(path.clone(), contents), |_| ())
+                    .then(move |()| iced::window::close(id))
+            }
+            _ => iced::window::close(id),
+        }
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    /// Moves a theme to the front of the recently viewed ones.
+    fn viewed(&mut self, id: ThemeId) {
+        self.recent_themes.retain(|recent| *recent != id);
+        self.recent_themes.insert(0, id);
+        self.recent_themes.truncate(search::RECENT);
+    }
 
-            text_input(content, usage, variant)
-        };
+    /// Every built-in and library theme, as the command palette sees them.
+    fn candidates(&self) -> Vec<search::Candidate> {
+        let built_in = self.themes.iter().map(|theme| {
+            search::Candidate::new(
+                ThemeId::BuiltIn(theme.to_string()),
+                theme.to_string(),
+                &[],
+                *theme.extended_palette(),
+            )
+        });
+
+        let library = self.library.iter().map(|entry| {
+            search::Candidate::new(
+                ThemeId::Entry(entry.path.clone()),
+                entry.file.metadata.name.clone(),
+                &entry.file.metadata.tags,
+                entry.file.extended(),
+            )
+        });
+
+        built_in.chain(library).collect()
+    }
 
-        widget::row!(
-            my_text("Background").width(Length::Fill),
-            base,
-            weak,
-            strong
-        )
+    /// The theme being viewed, or the built-in one edits are made to.
+    fn current_id(&self) -> ThemeId {
+        match &self.entry {
+            Some(path) => ThemeId::Entry(path.clone()),
+            None => ThemeId::BuiltIn(self.theme.to_string()),
+        }
     }
 
-    fn primary(&self, theme: &Theme) -> Row<'_, AppMessage> {
-        let usage = Usage::Primary;
-
-        let base = {
-            let variant = Variant::Base;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+    fn search_results(&self, search: &Search) -> Vec<(search::Section, search::Candidate)> {
+        search::results(
+            self.candidates(),
+            &search.query,
+            &self.favorites,
+            &self.recent_themes,
+        )
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    fn selected_entry(&self) -> Option<&library::Entry> {
+        let path = self.entry.as_ref()?;
 
-            text_input(content, usage, variant)
-        };
+        self.library.iter().find(|entry| &entry.path == path)
+    }
 
-        let weak = {
-            let variant = Variant::Weak;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+    fn current(&self) -> &Theme {
+        self.custom.as_ref().unwrap_or(&self.theme)
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    fn subscription(&self) -> iced::Subscription<AppMessage> {
+        let tick = iced::time::every(Duration::from_secs(1)).map(|_| AppMessage::Tick);
 
-            text_input(content, usage, variant)
-        };
+        let watch = self.watch.as_ref().map(|_| {
+            iced::time::every(Duration::from_millis(500)).map(|_| AppMessage::CheckWatched)
+        });
 
-        let strong = {
-            let variant = Variant::Strong;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        let close = iced::window::close_requests().map(AppMessage::CloseRequested);
+        let keyboard = iced::event::listen_with(keyboard);
 
-            let content = widget::text_input("rgb or hex", &value);
+        iced::Subscription::batch([tick, close, keyboard].into_iter().chain(watch))
+    }
 
-            text_input(content, usage, variant)
-        };
+    pub fn view(&self) -> iced::Element<'_, AppMessage> {
+        let content = widget::responsive(move |size| {
+            if size.width >= LIBRARY_WIDTH + GRID_WIDTH {
+                widget::row!(
+                    self.library(false),
+                    self.main(size.width - LIBRARY_WIDTH, false)
+                )
+                .into()
+            } else if self.show_library {
+                self.library(true)
+            } else {
+                self.main(size.width, true)
+            }
+        });
 
-        widget::row!(my_text("Primary").width(Length::Fill), base, weak, strong)
+        match &self.search {
+            Some(search) => widget::stack![content, self.search(search)].into(),
+            None => content.into(),
+        }
     }
 
-    fn secondary(&self, theme: &Theme) -> Row<'_, AppMessage> {
-        let usage = Usage::Secondary;
+    /// Everything but the library, laid out for the given width. Narrow
+    /// windows show the library in its place when asked to.
+    fn main(&self, width: f32, narrow: bool) -> iced::Element<'_, AppMessage> {
+        let theme = self.current();
 
-        let base = {
-            let variant = Variant::Base;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        let title = widget::text("Default Themes")
+            .size(32.0)
+            .center()
+            .width(Length::Fill);
 
-            let content = widget::text_input("rgb or hex", &value);
+        let search = widget::tooltip(
+            widget::button("Search").on_press(AppMessage::OpenSearch),
+            widget::container("Ctrl+P")
+                .padding(4.0)
+                .style(container::rounded_box),
+            widget::tooltip::Position::Bottom,
+        );
 
-            text_input(content, usage, variant)
+        // Narrow windows have no room for the buttons beside the title
+        let header: iced::Element<'_, AppMessage> = if narrow {
+            widget::column!(
+                title,
+                widget::row!(
+                    widget::button("Themes").on_press(AppMessage::ShowLibrary(true)),
+                    self.modes(),
+                    search,
+                )
+                .spacing(8.0)
+                .wrap()
+            )
+            .spacing(8.0)
+            .into()
+        } else {
+            widget::row!(title, self.modes(), search)
+                .spacing(8.0)
+                .align_y(Vertical::Center)
+                .into()
         };
 
-        let weak = {
-            let variant = Variant::Weak;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        let spacing = 16.0;
 
-            let content = widget::text_input("rgb or hex", &value);
+        if self.mode == Mode::Browser {
+            let browser = self
+                .browser
+                .view(self.candidates(), &self.current_id(), width >= GRID_WIDTH)
+                .map(AppMessage::Browser);
 
-            text_input(content, usage, variant)
-        };
+            return page(widget::column![header, browser].spacing(spacing));
+        }
 
-        let strong = {
-            let variant = Variant::Strong;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        if self.mode == Mode::Overview {
+            return page(widget::column![header, self.overview()].spacing(spacing));
+        }
 
-            let content = widget::text_input("rgb or hex", &value);
+        let content = if width >= GRID_WIDTH {
+            self.table(theme, spacing)
+        } else {
+            self.stacked(theme, spacing)
+        };
 
-            text_input(content, usage, variant)
+        let btn_message = if self.custom.is_some() {
+            Some(AppMessage::ResetCustom)
+        } else {
+            None
         };
+        let reset = widget::button("Reset Custom").on_press_maybe(btn_message);
+
+        let regenerate = widget::button("Regenerate").on_press(AppMessage::Regenerate);
+
+        let copy_format = widget::row!(
+            widget::text("Copy as"),
+            widget::pick_list(
+                color::Format::ALL,
+                Some(self.copy_format),
+                AppMessage::CopyFormat
+            ),
+        )
+        .spacing(8.0)
+        .align_y(Vertical::Center);
+
+        let undo = widget::button("Undo")
+            .on_press_maybe((!self.undo.is_empty()).then_some(AppMessage::Undo));
+        let redo = widget::button("Redo")
+            .on_press_maybe((!self.redo.is_empty()).then_some(AppMessage::Redo));
+
+        let ramps =
+            widget::checkbox("Tonal ramps", self.show_ramps).on_toggle(AppMessage::ShowRamps);
+
+        let derivations = widget::checkbox("Derivations", self.show_derivations)
+            .on_toggle(AppMessage::ShowDerivations);
+
+        let buttons = widget::row!(
+            undo,
+            redo,
+            regenerate,
+            reset,
+            copy_format,
+            ramps,
+            derivations
+        )
+        .spacing(spacing)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let file = widget::row!(
+            widget::text_input("Theme file path", &self.path)
+                .on_input(AppMessage::PathInput)
+                .on_submit(AppMessage::Open)
+                .width(250.0),
+            widget::button("Open").on_press(AppMessage::Open),
+            widget::button("Save").on_press(AppMessage::Save),
+            widget::button("Compare").on_press(AppMessage::Compare),
+            widget::checkbox("Watch", self.watch.is_some()).on_toggle(AppMessage::Watch),
+            widget::pick_list(
+                export::Format::ALL,
+                Some(self.export_format),
+                AppMessage::ExportFormat
+            ),
+            widget::button("Export").on_press(AppMessage::Export),
+        )
+        .spacing(spacing)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let generator = widget::row!(
+            widget::button("Surprise me").on_press(AppMessage::Surprise),
+            widget::checkbox("Dark", self.dark).on_toggle(AppMessage::Dark),
+            widget::pick_list(
+                MinContrast::ALL,
+                Some(self.min_contrast),
+                AppMessage::MinContrast
+            ),
+            widget::text_input("Seed", &self.seed)
+                .on_input(AppMessage::SeedInput)
+                .on_submit(AppMessage::Generate)
+                .width(200.0),
+        )
+        .spacing(spacing)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let ansi_mapping = self.terminal.as_ref().map(|_| {
+            widget::row(terminal::Mapping::USAGES.into_iter().map(|usage| {
+                widget::row!(
+                    widget::text(usage.label()),
+                    widget::pick_list(
+                        terminal::Ansi::ALL,
+                        self.ansi_mapping.get(usage),
+                        move |ansi| AppMessage::AnsiMapping(usage, ansi)
+                    ),
+                )
+                .spacing(8.0)
+                .align_y(Vertical::Center)
+                .into()
+            }))
+            .spacing(spacing)
+            .align_y(Vertical::Center)
+            .wrap()
+        });
+
+        let broadcast = widget::row!(
+            widget::text("Broadcast on"),
+            widget::text_input(iced_theme_client::DEFAULT_ADDRESS, &self.broadcast_address)
+                .on_input_maybe(
+                    self.broadcast
+                        .is_none()
+                        .then_some(AppMessage::BroadcastAddress)
+                )
+                .width(250.0),
+            widget::checkbox("", self.broadcast.is_some()).on_toggle(AppMessage::Broadcast),
+        )
+        .spacing(8.0)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let metadata = self.custom.as_ref().map(|_| {
+            widget::column!(
+                widget::row!(
+                    widget::text_input("Name", &self.metadata.name).on_input(AppMessage::NameInput),
+                    widget::text_input("Author", &self.metadata.author)
+                        .on_input(AppMessage::AuthorInput),
+                    widget::text_input("Tags, separated by commas", &self.tags)
+                        .on_input(AppMessage::TagsInput),
+                )
+                .spacing(8.0),
+                widget::text_input("Description", &self.metadata.description)
+                    .on_input(AppMessage::DescriptionInput),
+            )
+            .spacing(8.0)
+        });
+
+        let status = self.status.as_deref().map(widget::text);
+
+        let comparison = self.comparison.as_ref().map(|(name, before)| {
+            let diff = Diff::new(before, theme.extended_palette());
+
+            let title = match diff.is_dark {
+                Some((before, after)) => format!("Changes from {name} (dark: {before} → {after})"),
+                None if diff.changes.is_empty() => format!("No changes from {name}"),
+                None => format!("Changes from {name}"),
+            };
 
-        widget::row!(my_text("Secondary").width(Length::Fill), base, weak, strong)
+            let changes = diff.changes.into_iter().map(|change| {
+                widget::row!(
+                    widget::text(format!(
+                        "{} {}",
+                        change.usage.label(),
+                        change.variant.label()
+                    ))
+                    .width(Length::Fill),
+                    picker::swatch(change.before.color),
+                    widget::text("→"),
+                    picker::swatch(change.after.color),
+                    widget::text(format!("ΔE {:.2}", change.delta_e())).width(80.0),
+                )
+                .spacing(8.0)
+                .align_y(Vertical::Center)
+                .into()
+            });
+
+            widget::column!(
+                widget::row!(
+                    widget::text(title).width(Length::Fill),
+                    widget::button("Close").on_press(AppMessage::CloseComparison),
+                )
+                .align_y(Vertical::Center),
+                widget::container(widget::scrollable(
+                    widget::column(changes).spacing(4.0).padding([0, 16])
+                ))
+                .max_height(150.0),
+            )
+            .spacing(8.0)
+            .max_width(500.0)
+        });
+
+        let main = widget::column![header]
+            .push_maybe(metadata)
+            .push(content)
+            .push_maybe(
+                self.show_ramps
+                    .then(|| self.ramps(theme, width >= GRID_WIDTH)),
+            )
+            .push_maybe(self.show_derivations.then(|| self.derivations(theme)))
+            .push(widget::vertical_space().height(25.0))
+            .push(generator)
+            .push(file)
+            .push(broadcast)
+            .push_maybe(ansi_mapping)
+            .push_maybe(comparison)
+            .push_maybe(status)
+            .push(buttons)
+            .align_x(Horizontal::Center)
+            .spacing(spacing);
+
+        page(main)
     }
 
-    fn success(&self, theme: &Theme) -> Row<'_, AppMessage> {
-        let usage = Usage::Success;
+    /// The tonal ramp of every row, with the steps closest to its variants
+    /// marked.
+    fn ramps(&self, theme: &Theme, wide: bool) -> iced::Element<'_, AppMessage> {
+        let variants = widget::row(Variant::ALL.map(|variant| {
+            widget::button(variant.label())
+                .on_press(AppMessage::RampVariant(variant))
+                .style(if self.ramp_variant == variant {
+                    widget::button::primary
+                } else {
+                    widget::button::secondary
+                })
+                .into()
+        }));
+
+        let intro = widget::row!(
+            widget::text("Click a step to make it the"),
+            variants,
+            widget::text("color of its row."),
+        )
+        .spacing(8.0)
+        .align_y(Vertical::Center)
+        .wrap();
+
+        let legend =
+            widget::text("B, W and S mark the steps closest to the base, weak and strong colors.")
+                .size(12)
+                .style(widget::text::secondary);
+
+        let rows = Usage::ALL.map(|usage| {
+            let steps = ramp::ramp(theme.extended_palette(), usage)
+                .into_iter()
+                .map(|step| {
+                    let color = step.color;
+                    let text = if color::to_oklch(color).l > 0.6 {
+                        Color::BLACK
+                    } else {
+                        Color::WHITE
+                    };
+
+                    let marks = step
+                        .variants
+                        .iter()
+                        .map(|variant| &variant.label()[..1])
+                        .collect::<Vec<_>>()
+                        .join(" ");
+
+                    widget::button(
+                        widget::text(marks)
+                            .size(12)
+                            .center()
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .on_press(AppMessage::RampStep(usage, color))
+                    .width(Length::Fill)
+                    .height(32.0)
+                    .padding(0)
+                    .style(move |_, status| widget::button::Style {
+                        background: Some(Background::Color(color)),
+                        text_color: text,
+                        border: Border::default()
+                            .rounded(4.0)
+                            .width(if status == widget::button::Status::Hovered {
+                                2.0
+                            } else {
+                                0.0
+                            })
+                            .color(text),
+                        ..widget::button::Style::default()
+                    })
+                    .into()
+                });
+
+            let steps = widget::row(steps).spacing(2.0);
+            let label = widget::text(usage.label());
+
+            if wide {
+                widget::row!(label.width(LABEL_WIDTH), steps)
+                    .align_y(Vertical::Center)
+                    .into()
+            } else {
+                widget::column!(label, steps).spacing(4.0).into()
+            }
+        });
 
-        let base = {
-            let variant = Variant::Base;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        widget::column!(intro, legend)
+            .extend(rows)
+            .spacing(8.0)
+            .into()
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    /// How iced derives every cell from the theme's base palette, pointing out
+    /// the cells which were changed since.
+    fn derivations(&self, theme: &Theme) -> iced::Element<'_, AppMessage> {
+        let palette = theme.palette();
+
+        let cells = Usage::ALL.into_iter().flat_map(|usage| {
+            Variant::ALL.map(|variant| {
+                let derivation = derivation::derive(&palette, usage, variant);
+                let actual = get_pair(theme, usage, variant);
+
+                let edited = (actual != derivation.pair).then(|| {
+                    widget::text(format!(
+                        "Edited: iced derives {} with {} text",
+                        color::to_hex(derivation.pair.color),
+                        color::to_hex(derivation.pair.text),
+                    ))
+                    .size(14)
+                    .style(widget::text::danger)
+                });
+
+                let explanation = widget::column!(
+                    widget::text(format!("{} {}", usage.label(), variant.label())),
+                    widget::text(derivation.step.to_string()).size(14),
+                    widget::text(derivation.readability.to_string())
+                        .size(14)
+                        .style(widget::text::secondary),
+                )
+                .push_maybe(edited)
+                .spacing(2.0);
+
+                widget::row!(picker::swatch(actual.color), explanation)
+                    .spacing(12.0)
+                    .into()
+            })
+        });
+
+        widget::column!(widget::text(format!(
+            "Derived from the palette: background {}, text {}, primary {}, success {}, danger {}",
+            color::to_hex(palette.background),
+            color::to_hex(palette.text),
+            color::to_hex(palette.primary),
+            color::to_hex(palette.success),
+            color::to_hex(palette.danger),
+        )))
+        .extend(cells)
+        .spacing(12.0)
+        .width(Length::Fill)
+        .into()
+    }
 
-            text_input(content, usage, variant)
+    /// Switches between editing the selected theme and browsing them all.
+    fn modes(&self) -> iced::Element<'_, AppMessage> {
+        let mode = |label, mode| {
+            widget::button(label)
+                .on_press(AppMessage::Mode(mode))
+                .style(if self.mode == mode {
+                    widget::button::primary
+                } else {
+                    widget::button::secondary
+                })
+                .into()
         };
 
-        let weak = {
-            let variant = Variant::Weak;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        widget::row([
+            mode("Edit", Mode::Editor),
+            mode("Browse", Mode::Browser),
+            mode("Overview", Mode::Overview),
+        ])
+        .into()
+    }
 
-            let content = widget::text_input("rgb or hex", &value);
+    /// Every built-in theme as a thumbnail of its grid, to select one at a
+    /// glance.
+    fn overview(&self) -> iced::Element<'_, AppMessage> {
+        let thumbnails = self.themes.iter().map(|theme| {
+            let grid = widget::column(Usage::ALL.into_iter().map(|usage| {
+                widget::row(Variant::ALL.into_iter().map(|variant| {
+                    let color = get_pair(theme, usage, variant).color;
+
+                    widget::container(widget::Space::new(
+                        THUMBNAIL_SWATCH.width,
+                        THUMBNAIL_SWATCH.height,
+                    ))
+                    .style(move |_| container::Style::default().background(color))
+                    .into()
+                }))
+                .into()
+            }));
+
+            let selected = self.entry.is_none() && *theme == self.theme;
+
+            widget::button(
+                widget::column!(grid, widget::text(theme.to_string()).size(14).center())
+                    .spacing(4.0)
+                    .width(THUMBNAIL_SWATCH.width * 3.0)
+                    .align_x(Horizontal::Center),
+            )
+            .on_press(AppMessage::Select(theme.clone()))
+            .padding(8)
+            .style(if selected {
+                widget::button::primary
+            } else {
+                widget::button::text
+            })
+            .into()
+        });
+
+        widget::row(thumbnails).spacing(8.0).wrap().into()
+    }
 
-            text_input(content, usage, variant)
+    /// The grid with a column per variant, aligned with their names above.
+    fn table<'a>(&'a self, theme: &'a Theme, spacing: f32) -> iced::Element<'a, AppMessage> {
+        let labels = Variant::ALL.into_iter().map(|variant| {
+            widget::row!(
+                widget::text(variant.label()).center().width(Length::Fill),
+                widget::horizontal_space().width(LOCK_SIZE)
+            )
+            .spacing(LOCK_SPACING)
+            .width(Length::Fill)
+            .into()
+        });
+
+        let labels = widget::row!(widget::horizontal_space().width(LABEL_WIDTH))
+            .extend(labels)
+            .spacing(spacing);
+
+        let rows = Usage::ALL.into_iter().map(|usage| {
+            let cells = Variant::ALL.into_iter().map(|variant| {
+                widget::container(self.cell(theme, usage, variant))
+                    .width(Length::Fill)
+                    .into()
+            });
+
+            widget::row!(
+                widget::container(self.label(usage))
+                    .center_y(64.0)
+                    .width(LABEL_WIDTH)
+            )
+            .extend(cells)
+            .spacing(spacing)
+            .align_y(Vertical::Center)
+            .into()
+        });
+
+        widget::column!(labels, widget::column(rows).spacing(spacing))
+            .spacing(spacing)
+            .into()
+    }
+
+    /// The grid with one row below the other, for narrow windows.
+    fn stacked<'a>(&'a self, theme: &'a Theme, spacing: f32) -> iced::Element<'a, AppMessage> {
+        widget::column(Usage::ALL.into_iter().map(|usage| {
+            let cells = Variant::ALL.into_iter().map(|variant| {
+                widget::row!(
+                    widget::text(variant.label()).width(VARIANT_WIDTH),
+                    self.cell(theme, usage, variant)
+                )
+                .spacing(8.0)
+                .align_y(Vertical::Center)
+                .into()
+            });
+
+            widget::column!(self.label(usage))
+                .extend(cells)
+                .spacing(8.0)
+                .into()
+        }))
+        .spacing(spacing)
+        .into()
+    }
+
+    /// The sidebar listing the built-in and user themes, which takes the
+    /// whole window when it is narrow.
+    fn library(&self, narrow: bool) -> iced::Element<'_, AppMessage> {
+        let item = |name: String, author: &str, selected: bool, message| {
+            let author =
+                (!author.is_empty()).then(|| widget::text(format!("by {author}")).size(12));
+
+            widget::button(widget::column!(widget::text(name)).push_maybe(author))
+                .on_press(message)
+                .width(Length::Fill)
+                .padding([4, 8])
+                .style(if selected {
+                    widget::button::primary
+                } else {
+                    widget::button::text
+                })
+                .into()
         };
 
-        let strong = {
-            let variant = Variant::Strong;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
+        let groups = library::Group::ALL.into_iter().map(|group| {
+            let items: Vec<_> = match group {
+                library::Group::BuiltIn => self
+                    .themes
+                    .iter()
+                    .map(|theme| {
+                        let selected = self.entry.is_none() && *theme == self.theme;
+                        item(
+                            theme.to_string(),
+                            "",
+                            selected,
+                            AppMessage::Select(theme.clone()),
+                        )
+                    })
+                    .collect(),
+                group => self
+                    .library
+                    .iter()
+                    .filter(|entry| entry.group == group)
+                    .map(|entry| {
+                        let selected = self.entry.as_ref() == Some(&entry.path);
+                        item(
+                            entry.file.metadata.name.clone(),
+                            &entry.file.metadata.author,
+                            selected,
+                            AppMessage::SelectEntry(entry.path.clone()),
+                        )
+                    })
+                    .collect(),
             };
 
-            let content = widget::text_input("rgb or hex", &value);
+            let empty = items
+                .is_empty()
+                .then(|| widget::text("No themes yet").style(widget::text::secondary));
+
+            widget::column!(widget::text(group.to_string()).size(14))
+                .extend(items)
+                .push_maybe(empty)
+                .spacing(2.0)
+                .into()
+        });
+
+        let list = widget::scrollable(widget::column(groups).spacing(12.0).padding([0, 12]))
+            .spacing(0)
+            .height(Length::Fill);
+
+        let can_store = self.library_directory.is_some();
+
+        let actions: iced::Element<'_, AppMessage> = if let Some(entry) = self
+            .selected_entry()
+            .filter(|entry| self.deleting.as_ref() == Some(&entry.path))
+        {
+            widget::column!(
+                widget::text(format!(
+                    "Delete {} from the library? This cannot be undone.",
+                    entry.file.metadata.name
+                )),
+                widget::row!(
+                    widget::button("Delete")
+                        .on_press(AppMessage::ConfirmDelete)
+                        .style(widget::button::danger),
+                    widget::button("Cancel")
+                        .on_press(AppMessage::CancelDelete)
+                        .style(widget::button::secondary),
+                )
+                .spacing(8.0),
+            )
+            .spacing(8.0)
+            .into()
+        } else if self.entry.is_some() {
+            widget::column!(
+                widget::button("Save")
+                    .on_press_maybe(self.is_named().then_some(AppMessage::SaveEntry)),
+                widget::row!(
+                    widget::button("Duplicate").on_press(AppMessage::DuplicateEntry),
+                    widget::button("Delete")
+                        .on_press(AppMessage::DeleteEntry)
+                        .style(widget::button::danger),
+                )
+                .spacing(8.0),
+            )
+            .spacing(8.0)
+            .into()
+        } else {
+            let label = if self.custom.is_some() {
+                "Add to library"
+            } else {
+                "Fork"
+            };
 
-            text_input(content, usage, variant)
+            widget::button(label)
+                .on_press_maybe((can_store && self.is_named()).then_some(AppMessage::AddToLibrary))
+                .into()
         };
 
-        widget::row!(my_text("Success").width(Length::Fill), base, weak, strong)
+        let back = narrow.then(|| {
+            widget::container(widget::button("Back").on_press(AppMessage::ShowLibrary(false)))
+                .padding([0, 12])
+        });
+
+        widget::column![]
+            .push_maybe(back)
+            .push(list)
+            .push(widget::container(actions).padding([0, 12]))
+            .spacing(12.0)
+            .padding([16, 0])
+            .width(if narrow {
+                Length::Fill
+            } else {
+                Length::Fixed(LIBRARY_WIDTH)
+            })
+            .into()
     }
 
-    fn danger(&self, theme: &Theme) -> Row<'_, AppMessage> {
-        let usage = Usage::Danger;
+    /// The command palette, shown over everything else until a theme is
+    /// picked or it is dismissed.
+    fn search(&self, search: &Search) -> iced::Element<'_, AppMessage> {
+        let input = widget::text_input("Search themes by name, tag or color", &search.query)
+            .id(search_id())
+            .on_input(AppMessage::SearchInput)
+            .on_submit(AppMessage::SearchSubmit)
+            .padding(10);
+
+        let mut results = widget::column![].spacing(2.0);
+        let mut section = None;
+
+        for (index, (current, candidate)) in self.search_results(search).into_iter().enumerate() {
+            if section != Some(current) {
+                section = Some(current);
+                results = results.push(
+                    widget::text(current.to_string())
+                        .size(14)
+                        .style(widget::text::secondary),
+                );
+            }
 
-        let base = {
-            let variant = Variant::Base;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
+            let colors = widget::row(
+                [
+                    candidate.palette.background.base.color,
+                    candidate.palette.primary.base.color,
+                    candidate.palette.success.base.color,
+                    candidate.palette.danger.base.color,
+                ]
+                .map(|color| picker::swatch(color).into()),
+            )
+            .spacing(2.0);
+
+            let name = widget::column!(
+                widget::text(candidate.name),
+                widget::text(candidate.keywords.join(", ")).size(12),
+            );
+
+            let open = widget::button(
+                widget::row!(colors, name)
+                    .spacing(12.0)
+                    .align_y(Vertical::Center),
+            )
+            .on_press(AppMessage::OpenTheme(candidate.id.clone()))
+            .width(Length::Fill)
+            .padding([4, 8])
+            .style(if index == search.selected {
+                widget::button::primary
+            } else {
+                widget::button::text
+            });
+
+            let favorite = if self.favorites.contains(&candidate.id) {
+                "Unpin"
+            } else {
+                "Pin"
             };
 
-            let content = widget::text_input("rgb or hex", &value);
+            let favorite = widget::button(widget::text(favorite).size(14))
+                .on_press(AppMessage::ToggleFavorite(candidate.id))
+                .style(widget::button::text);
 
-            text_input(content, usage, variant)
-        };
+            results = results.push(
+                widget::row!(open, favorite)
+                    .spacing(4.0)
+                    .align_y(Vertical::Center),
+            );
+        }
 
-        let weak = {
-            let variant = Variant::Weak;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        if section.is_none() {
+            results = results.push(widget::text("No themes match").style(widget::text::secondary));
+        }
 
-            let content = widget::text_input("rgb or hex", &value);
+        let results = widget::scrollable(results).id(results_id()).spacing(0);
+
+        let panel = widget::container(widget::column!(input, results).spacing(12.0))
+            .padding(12.0)
+            .width(480.0)
+            .max_height(480.0)
+            .style(|theme: &Theme| container::Style {
+                shadow: iced::Shadow {
+                    color: Color::BLACK.scale_alpha(0.3),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..container::bordered_box(theme).border(Border::default().rounded(6.0))
+            });
+
+        widget::opaque(
+            widget::mouse_area(
+                widget::center(widget::opaque(panel))
+                    .padding(16.0)
+                    .style(|_| {
+                        container::Style::default().background(Color::BLACK.scale_alpha(0.5))
+                    }),
+            )
+            .on_press(AppMessage::CloseSearch),
+        )
+    }
 
-            text_input(content, usage, variant)
+    /// The name of a row, which opens a menu to copy or paste it.
+    fn label(&self, usage: Usage) -> iced::Element<'_, AppMessage> {
+        let label = widget::button(usage.label())
+            .on_press(AppMessage::OpenRowMenu(usage))
+            .padding(0)
+            .style(widget::button::text);
+
+        let menu = (self.row_menu == Some(usage)).then(|| {
+            widget::container(
+                widget::column!(
+                    widget::button("Copy row")
+                        .on_press(AppMessage::CopyRow(usage))
+                        .style(widget::button::text),
+                    widget::button("Paste row")
+                        .on_press(AppMessage::PasteRow(usage))
+                        .style(widget::button::text),
+                )
+                .spacing(4.0),
+            )
+            .padding(4.0)
+            .style(container::bordered_box)
+            .into()
+        });
+
+        popover::popover(label, menu)
+            .on_dismiss(AppMessage::CloseRowMenu)
+            .into()
+    }
+
+    fn cell(&self, theme: &Theme, usage: Usage, variant: Variant) -> iced::Element<'_, AppMessage> {
+        let value = match self.pending {
+            Some(Pending {
+                usage: pending_usage,
+                variant: pending_variant,
+            }) if usage == pending_usage && variant == pending_variant => self
+                .custom_input
+                .clone()
+                .unwrap_or_else(|| theme_str(theme, usage, variant)),
+            _ => theme_str(theme, usage, variant),
         };
 
-        let strong = {
-            let variant = Variant::Strong;
-            let value = match self.pending {
-                Some(Pending {
-                    usage: pending_usage,
-                    variant: pending_variant,
-                }) if usage == pending_usage && variant == pending_variant => self
-                    .custom_input
-                    .clone()
-                    .unwrap_or_else(|| theme_str(theme, usage, variant)),
-                _ => theme_str(theme, usage, variant),
-            };
+        let content = widget::text_input("rgb or hex", &value).id(input_id(usage, variant));
 
-            let content = widget::text_input("rgb or hex", &value);
+        let lock = widget::checkbox("", self.locked.contains(&(usage, variant)))
+            .on_toggle(move |locked| AppMessage::Lock(usage, variant, locked))
+            .size(LOCK_SIZE)
+            .spacing(0);
 
-            text_input(content, usage, variant)
-        };
+        let lock = widget::tooltip(
+            lock,
+            widget::container("Lock")
+                .padding(4.0)
+                .style(container::rounded_box),
+            widget::tooltip::Position::Top,
+        );
+
+        let swatch_color = get_pair(theme, usage, variant).color;
+
+        let swatch = widget::button(widget::Space::new(LOCK_SIZE, LOCK_SIZE))
+            .on_press(AppMessage::OpenPicker(usage, variant))
+            .padding(0)
+            .style(move |theme: &Theme, _status| widget::button::Style {
+                background: Some(Background::Color(swatch_color)),
+                border: Border::default()
+                    .rounded(4.0)
+                    .width(1.0)
+                    .color(theme.extended_palette().background.strong.color),
+                ..widget::button::Style::default()
+            });
+
+        let controls = widget::column!(swatch, lock).spacing(LOCK_SPACING);
+
+        let content = nudge::nudge(
+            text_input(
+                content,
+                usage,
+                variant,
+                self.selected == Some((usage, variant)),
+            ),
+            move |channel, steps| AppMessage::Nudge(usage, variant, channel, steps),
+        );
+
+        let cell = widget::row!(content, controls)
+            .spacing(LOCK_SPACING)
+            .align_y(Vertical::Center);
+
+        let popup = self
+            .picker
+            .as_ref()
+            .filter(|picker| picker.usage == usage && picker.variant == variant)
+            .map(|picker| picker.view(&self.recent).map(AppMessage::Picker));
 
-        widget::row!(my_text("Danger").width(Length::Fill), base, weak, strong)
+        popover::popover(cell, popup)
+            .on_dismiss(AppMessage::ClosePicker)
+            .into()
     }
 }
 
 fn main() -> iced::Result {
+    let fresh = match cli::Command::parse(std::env::args().skip(1)) {
+        Ok(cli::Command::View { fresh }) => fresh,
+        Ok(command) => {
+            if let Err(error) = command.run() {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+
+            return Ok(());
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     iced::application("App", App::update, App::view)
         .antialiasing(true)
-        .window_size((720.0, 720.0))
-        .resizable(false)
+        .window(iced::window::Settings {
+            size: Size::new(1000.0, 760.0),
+            min_size: Some(Size::new(320.0, 320.0)),
+            ..iced::window::Settings::default()
+        })
         .theme(theme)
         .subscription(App::subscription)
-        .run_with(|| {
-            let state = App::new();
-            (state, iced::Task::none())
+        .exit_on_close_request(false)
+        .run_with(move || {
+            let mut state = App::new();
+            state.restore(fresh);
+
+            let task = state.load_library();
+            (state, task)
         })
 }
 
+/// Centers the content of the main part of the window, scrolling it when
+/// it does not fit.
+fn page(content: widget::Column<'_, AppMessage>) -> iced::Element<'_, AppMessage> {
+    let content = content
+        .padding(16.0)
+        .width(Length::Fill)
+        .max_width(MAX_WIDTH);
+
+    widget::scrollable(widget::container(content).center_x(Length::Fill))
+        .spacing(0)
+        .into()
+}
+
+/// Whether a message may change what the session holds. Timers and
+/// notifications, which arrive all the time, do not.
+fn changes_session(message: &AppMessage) -> bool {
+    !matches!(
+        message,
+        AppMessage::Tick
+            | AppMessage::CheckWatched
+            | AppMessage::WatchedModified(_)
+            | AppMessage::SessionSaved(_)
+            | AppMessage::CloseRequested(_)
+            | AppMessage::Saved(_)
+    )
+}
+
 fn theme(app: &App) -> Theme {
     app.custom.clone().unwrap_or_else(|| app.theme.clone())
 }
 
+/// Turns a key press which nothing else handled into a message.
+fn keyboard(
+    event: iced::Event,
+    status: iced::event::Status,
+    _window: iced::window::Id,
+) -> Option<AppMessage> {
+    use iced::keyboard::{Event, Key, key::Named};
+
+    let iced::Event::Keyboard(Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+
+    // Text inputs do nothing with the shortcut, so it works while typing.
+    // Shift and Caps Lock turn the key into an uppercase P.
+    if modifiers.command()
+        && matches!(key.as_ref(), Key::Character(c) if c.eq_ignore_ascii_case("p"))
+    {
+        return Some(AppMessage::OpenSearch);
+    }
+
+    let Key::Named(key) = key else {
+        return None;
+    };
+
+    // Text inputs handle Escape by no longer being focused, which should
+    // drop what was typed into them as well
+    if key == Named::Escape {
+        return Some(AppMessage::Cancel);
+    }
+
+    if status == iced::event::Status::Captured {
+        return None;
+    }
+
+    match (key, modifiers.command()) {
+        (Named::ArrowUp, true) => Some(AppMessage::CycleTheme(-1)),
+        (Named::ArrowDown, true) => Some(AppMessage::CycleTheme(1)),
+        (Named::ArrowUp, false) => Some(AppMessage::Move(-1, 0)),
+        (Named::ArrowDown, false) => Some(AppMessage::Move(1, 0)),
+        (Named::ArrowLeft, false) => Some(AppMessage::Move(0, -1)),
+        (Named::ArrowRight, false) => Some(AppMessage::Move(0, 1)),
+        (Named::Enter, false) => Some(AppMessage::Edit),
+        _ => None,
+    }
+}
+
+/// The item `by` places away from `current`, stopping at either end.
+fn offset<T: Copy + PartialEq>(all: &[T], current: T, by: isize) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0) as isize;
+
+    all[(index + by).clamp(0, all.len() as isize - 1) as usize]
+}
+
+fn search_id() -> widget::text_input::Id {
+    widget::text_input::Id::new("search")
+}
+
+fn results_id() -> widget::scrollable::Id {
+    widget::scrollable::Id::new("search results")
+}
+
+fn input_id(usage: Usage, variant: Variant) -> widget::text_input::Id {
+    widget::text_input::Id::new(format!("{}-{}", usage.label(), variant.label()))
+}
+
 fn text_input(
     input: TextInput<'_, AppMessage>,
     usage: Usage,
     variant: Variant,
+    selected: bool,
 ) -> widget::TextInput<'_, AppMessage> {
     input
         .on_input(move |input| AppMessage::Action(input, usage, variant))
         .on_submit(AppMessage::ApplyCustom)
-        .width(168.0)
+        .width(Length::Fill)
         .padding([10, 8])
-        .style(move |theme, status| text_input_style(theme, status, usage, variant))
+        .style(move |theme, status| text_input_style(theme, status, usage, variant, selected))
 }
 
 fn convert_color_str(input: &str) -> Option<Pair> {
-    if input.is_empty() {
-        return None;
-    }
-
-    let input = input.trim();
-
-    let color = if input.contains(",") {
-        let values = input
-            .trim_start_matches("rgb(")
-            .trim_end_matches(")")
-            .split(",")
-            .filter_map(|split| split.trim().parse::<u8>().ok())
-            .collect::<Vec<u8>>();
-
-        if values.len() != 3 {
-            return None;
-        }
-
-        color!(values[0], values[1], values[2])
-    } else if input.contains("#") {
-        let value = u32::from_str_radix(input.trim_start_matches("#"), 16).ok()?;
-        color!(value)
-    } else {
-        let hex = u32::from_str_radix(input.trim(), 16).ok()?;
-        color!(hex)
-    };
+    let color = color::parse(input)?;
 
     let brightness = ((299.0 * color.r) + (587.0 * color.g) + (144.0 * color.b)) / 1000.0;
 
@@ -645,6 +2507,7 @@ fn text_input_style(
     status: widget::text_input::Status,
     usage: Usage,
     variant: Variant,
+    selected: bool,
 ) -> widget::text_input::Style {
     use widget::text_input::{Style, default};
 
@@ -652,8 +2515,19 @@ fn text_input_style(
 
     let pair = get_pair(theme, usage, variant);
 
+    let border = if selected {
+        Border {
+            color: pair.text,
+            width: 2.0,
+            ..default.border
+        }
+    } else {
+        default.border
+    };
+
     Style {
         background: Background::Color(pair.color),
+        border,
         value: pair.text,
         placeholder: pair.text.scale_alpha(0.25),
         ..default
@@ -661,7 +2535,10 @@ fn text_input_style(
 }
 
 fn get_pair(theme: &Theme, usage: Usage, variant: Variant) -> Pair {
-    let palette = theme.extended_palette();
+    pair(theme.extended_palette(), usage, variant)
+}
+
+fn pair(palette: &Extended, usage: Usage, variant: Variant) -> Pair {
     match usage {
         Usage::Primary => {
             let primary = palette.primary;
@@ -706,15 +2583,37 @@ fn get_pair(theme: &Theme, usage: Usage, variant: Variant) -> Pair {
     }
 }
 
+fn custom_theme(name: String, extended: Extended) -> Theme {
+    let custom = theme::Custom::with_fn(name, base_palette(&extended), move |_| extended);
+
+    Theme::Custom(Arc::new(custom))
+}
+
+/// The base [`Palette`] an [`Extended`] palette would be generated from.
+fn base_palette(extended: &Extended) -> Palette {
+    Palette {
+        background: extended.background.base.color,
+        text: extended.background.base.text,
+        primary: extended.primary.base.color,
+        success: extended.success.base.color,
+        danger: extended.danger.base.color,
+    }
+}
+
+/// Replaces the locked cells of a freshly generated palette with the ones
+/// from the current palette.
+fn with_locked(generated: Extended, current: &Extended, locked: &[(Usage, Variant)]) -> Extended {
+    locked
+        .iter()
+        .fold(generated, |extended, &(usage, variant)| {
+            updated_extended(extended, pair(current, usage, variant), usage, variant)
+        })
+}
+
 fn theme_str(theme: &Theme, usage: Usage, variant: Variant) -> String {
     let color = get_pair(theme, usage, variant).color;
 
-    format!(
-        "rgb({:.0}, {:.0}, {:.0})",
-        color.r * 255.0,
-        color.g * 255.0,
-        color.b * 255.0
-    )
+    color::Format::Rgb.format(color)
 }
 
 fn updated_extended(extended: Extended, pair: Pair, usage: Usage, variant: Variant) -> Extended {
@@ -829,3 +2728,32 @@ fn updated_extended(extended: Extended, pair: Pair, usage: Usage, variant: Varia
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_colors_keeps_text_readable() {
+        let mut app = App::new();
+        let _ = app.handle(AppMessage::Select(Theme::Light));
+
+        let dark = color!(0x202020);
+        app.set_colors(Usage::Background, &[(Variant::Base, dark)]);
+
+        let pair = get_pair(app.current(), Usage::Background, Variant::Base);
+
+        assert_eq!(pair.color, dark);
+        assert_eq!(pair.text, Color::WHITE);
+
+        // Text which is still readable is kept
+        let text = get_pair(app.current(), Usage::Primary, Variant::Base).text;
+        let light = color!(0xa0c0ff);
+        app.set_colors(Usage::Primary, &[(Variant::Base, light)]);
+
+        assert_eq!(
+            get_pair(app.current(), Usage::Primary, Variant::Base).text,
+            text
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Nudges the color of a cell being edited with the arrow keys or the mouse
+//! wheel.
+//!
+//! Up and down change the OKLCH lightness, holding Alt changes the chroma
+//! and holding Ctrl changes the hue instead. Shift makes every step larger.
+use crate::color;
+use iced::{
+    Color, Element, Event, Length, Rectangle, Size, Vector,
+    advanced::{
+        Clipboard, Layout, Shell, Widget, layout, mouse, overlay, renderer,
+        widget::{
+            Id, Operation, Tree,
+            operation::{Focusable, Outcome},
+            tree,
+        },
+    },
+    event,
+    keyboard::{self, key::Named},
+};
+
+/// How many small steps a large step is worth.
+const LARGE_STEP: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Channel {
+    Lightness,
+    Chroma,
+    Hue,
+}
+
+impl Channel {
+    /// The size of a single small step in OKLCH units.
+    pub fn step(self) -> f32 {
+        match self {
+            Channel::Lightness => 0.01,
+            Channel::Chroma => 0.005,
+            Channel::Hue => 1.0,
+        }
+    }
+}
+
+/// Moves `color` by the given number of steps along an OKLCH channel.
+pub fn apply(color: Color, channel: Channel, steps: f32) -> Color {
+    let oklch = color::to_oklch(color);
+
+    let amount = channel.step() * steps;
+    let (lightness, chroma, hue) = (oklch.l, oklch.chroma, oklch.hue.into_positive_degrees());
+
+    let nudged = match channel {
+        Channel::Lightness => color::from_oklch((lightness + amount).clamp(0.0, 1.0), chroma, hue),
+        Channel::Chroma => color::from_oklch(lightness, (chroma + amount).max(0.0), hue),
+        Channel::Hue => color::from_oklch(lightness, chroma, hue + amount),
+    };
+
+    Color {
+        a: color.a,
+        ..nudged
+    }
+}
+
+pub struct Nudge<'a, Message> {
+    content: Element<'a, Message>,
+    on_nudge: Box<dyn Fn(Channel, f32) -> Message + 'a>,
+}
+
+/// Wraps `content`, producing a message with the channel and the signed
+/// number of steps whenever it is nudged.
+pub fn nudge<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    on_nudge: impl Fn(Channel, f32) -> Message + 'a,
+) -> Nudge<'a, Message> {
+    Nudge {
+        content: content.into(),
+        on_nudge: Box::new(on_nudge),
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    modifiers: keyboard::Modifiers,
+}
+
+/// Finds out whether any text input inside a widget is focused.
+struct Focused(bool);
+
+impl Operation for Focused {
+    fn container(
+        &mut self,
+        _id: Option<&Id>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn focusable(&mut self, state: &mut dyn Focusable, _id: Option<&Id>) {
+        self.0 |= state.is_focused();
+    }
+
+    fn finish(&self) -> Outcome<()> {
+        Outcome::None
+    }
+}
+
+impl<Message> Nudge<'_, Message> {
+    fn publish(&self, amount: f32, modifiers: keyboard::Modifiers, shell: &mut Shell<'_, Message>) {
+        let channel = if modifiers.alt() {
+            Channel::Chroma
+        } else if modifiers.control() {
+            Channel::Hue
+        } else {
+            Channel::Lightness
+        };
+
+        let steps = if modifiers.shift() {
+            amount * LARGE_STEP
+        } else {
+            amount
+        };
+
+        shell.publish((self.on_nudge)(channel, steps));
+    }
+}
+
+impl<Message> Widget<Message, iced::Theme, iced::Renderer> for Nudge<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            tree.state.downcast_mut::<State>().modifiers = modifiers;
+        }
+
+        let status = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+
+        if status == event::Status::Captured {
+            return status;
+        }
+
+        // Whether the cell was clicked or moved to with the keyboard
+        let mut focused = Focused(false);
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, &mut focused);
+
+        if !focused.0 {
+            return status;
+        }
+
+        let state = tree.state.downcast_ref::<State>();
+
+        match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key @ (Named::ArrowUp | Named::ArrowDown)),
+                modifiers,
+                ..
+            }) => {
+                let amount = if key == Named::ArrowUp { 1.0 } else { -1.0 };
+                self.publish(amount, modifiers, shell);
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if cursor.is_over(layout.bounds()) =>
+            {
+                let (x, y) = match delta {
+                    mouse::ScrollDelta::Lines { x, y } | mouse::ScrollDelta::Pixels { x, y } => {
+                        (x, y)
+                    }
+                };
+
+                // Some platforms turn Shift + wheel into horizontal scrolling
+                let amount = if y != 0.0 { y } else { x };
+
+                if amount == 0.0 {
+                    return status;
+                }
+
+                self.publish(amount.signum(), state.modifiers, shell);
+
+                event::Status::Captured
+            }
+            _ => status,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, iced::Theme, iced::Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+}
+
+impl<'a, Message: 'a> From<Nudge<'a, Message>> for Element<'a, Message> {
+    fn from(nudge: Nudge<'a, Message>) -> Self {
+        Element::new(nudge)
+    }
+}
@@ -0,0 +1,337 @@
+//! A visual color picker for a single cell of the grid.
+use crate::{Usage, Variant, color::to_hex};
+use iced::{
+    Border, Color, Element, Length, Point, Rectangle, Renderer, Theme,
+    alignment::Vertical,
+    mouse,
+    widget::{
+        self,
+        canvas::{self, Frame, Geometry, Path, Stroke, event, gradient},
+        container,
+    },
+};
+use palette::{FromColor, Hsl, Hsv, Srgb};
+
+const SQUARE_WIDTH: f32 = 240.0;
+const SQUARE_HEIGHT: f32 = 140.0;
+const SWATCH_SIZE: f32 = 20.0;
+
+/// How many recently picked colors are remembered.
+pub const RECENT: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Picker {
+    pub usage: Usage,
+    pub variant: Variant,
+    original: Color,
+    color: Color,
+    /// Kept separately, since it is lost for grays.
+    hue: f32,
+    hex: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Hue(f32),
+    Saturation(f32),
+    Lightness(f32),
+    Alpha(f32),
+    SaturationValue(f32, f32),
+    Hex(String),
+    Recent(Color),
+    Copy,
+    Paste,
+}
+
+impl Picker {
+    pub fn new(usage: Usage, variant: Variant, color: Color) -> Self {
+        Self {
+            usage,
+            variant,
+            original: color,
+            color,
+            hue: hsl(color).hue.into_positive_degrees(),
+            hex: to_hex(color),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn changed(&self) -> bool {
+        self.color != self.original
+    }
+
+    /// Applies a [`Message`], returning the new color if it changed.
+    pub fn update(&mut self, message: Message) -> Option<Color> {
+        let alpha = self.color.a;
+
+        let color = match message {
+            Message::Hue(hue) => {
+                self.hue = hue;
+                from_hsl(
+                    Hsl {
+                        hue: hue.into(),
+                        ..hsl(self.color)
+                    },
+                    alpha,
+                )
+            }
+            Message::Saturation(saturation) => from_hsl(
+                Hsl {
+                    hue: self.hue.into(),
+                    saturation,
+                    ..hsl(self.color)
+                },
+                alpha,
+            ),
+            Message::Lightness(lightness) => from_hsl(
+                Hsl {
+                    hue: self.hue.into(),
+                    lightness,
+                    ..hsl(self.color)
+                },
+                alpha,
+            ),
+            Message::Alpha(alpha) => Color {
+                a: alpha,
+                ..self.color
+            },
+            Message::SaturationValue(saturation, value) => {
+                let rgb = Srgb::from_color(Hsv::new(self.hue, saturation, value));
+                Color {
+                    a: alpha,
+                    ..rgb.into()
+                }
+            }
+            Message::Hex(hex) => {
+                let color = hex.is_ascii().then(|| Color::parse(hex.trim())).flatten();
+                self.hex = hex;
+
+                let color = color?;
+                self.set_hue(color);
+                self.color = color;
+
+                return Some(color);
+            }
+            Message::Recent(color) => {
+                self.set_hue(color);
+                color
+            }
+            Message::Copy | Message::Paste => return None,
+        };
+
+        self.color = color;
+        self.hex = to_hex(color);
+
+        Some(color)
+    }
+
+    fn set_hue(&mut self, color: Color) {
+        let hsl = hsl(color);
+
+        if hsl.saturation > 0.0 {
+            self.hue = hsl.hue.into_positive_degrees();
+        }
+    }
+
+    pub fn view<'a>(&'a self, recent: &'a [Color]) -> Element<'a, Message> {
+        let hsl = hsl(self.color);
+        let hsv = Hsv::from_color(Srgb::from(self.color));
+
+        let square = widget::canvas(SaturationValue {
+            hue: self.hue,
+            saturation: hsv.saturation,
+            value: hsv.value,
+        })
+        .width(SQUARE_WIDTH)
+        .height(SQUARE_HEIGHT);
+
+        let slider = |label, range, value, step, on_change: fn(f32) -> Message| {
+            widget::row!(
+                widget::text(label).width(16.0),
+                widget::slider(range, value, on_change).step(step),
+            )
+            .spacing(8.0)
+            .align_y(Vertical::Center)
+        };
+
+        let preview = swatch(self.color).width(Length::Fill);
+
+        let hex = widget::row!(
+            widget::text_input("#rrggbbaa", &self.hex)
+                .on_input(Message::Hex)
+                .width(120.0),
+            preview,
+        )
+        .spacing(8.0)
+        .align_y(Vertical::Center);
+
+        let recent = widget::row(recent.iter().map(|&color| {
+            widget::button(swatch(color))
+                .on_press(Message::Recent(color))
+                .padding(0)
+                .style(widget::button::text)
+                .into()
+        }))
+        .spacing(4.0);
+
+        let clipboard = widget::row!(
+            widget::button("Copy").on_press(Message::Copy),
+            widget::button("Paste").on_press(Message::Paste),
+        )
+        .spacing(8.0);
+
+        let content = widget::column!(
+            square,
+            slider("H", 0.0..=360.0, self.hue, 1.0, Message::Hue),
+            slider("S", 0.0..=1.0, hsl.saturation, 0.01, Message::Saturation),
+            slider("L", 0.0..=1.0, hsl.lightness, 0.01, Message::Lightness),
+            slider("A", 0.0..=1.0, self.color.a, 0.01, Message::Alpha),
+            hex,
+            recent,
+            clipboard,
+        )
+        .spacing(8.0)
+        .width(SQUARE_WIDTH);
+
+        widget::container(content)
+            .padding(8.0)
+            .style(|theme: &Theme| container::Style {
+                shadow: iced::Shadow {
+                    color: Color::BLACK.scale_alpha(0.3),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..container::bordered_box(theme).border(Border::default().rounded(6.0))
+            })
+            .into()
+    }
+}
+
+pub fn swatch<'a, M: 'a>(color: Color) -> widget::Container<'a, M> {
+    widget::container(widget::Space::new(SWATCH_SIZE, SWATCH_SIZE)).style(move |theme: &Theme| {
+        container::Style::default().background(color).border(
+            Border::default()
+                .rounded(4.0)
+                .width(1.0)
+                .color(theme.extended_palette().background.strong.color),
+        )
+    })
+}
+
+fn hsl(color: Color) -> Hsl {
+    Hsl::from_color(Srgb::from(color))
+}
+
+fn from_hsl(hsl: Hsl, alpha: f32) -> Color {
+    let rgb: Color = Srgb::from_color(hsl).into();
+    Color { a: alpha, ..rgb }
+}
+
+/// A square picking HSV saturation horizontally and value vertically.
+struct SaturationValue {
+    hue: f32,
+    saturation: f32,
+    value: f32,
+}
+
+impl SaturationValue {
+    fn pick(bounds: Rectangle, position: Point) -> Message {
+        let saturation = ((position.x - bounds.x) / bounds.width).clamp(0.0, 1.0);
+        let value = 1.0 - ((position.y - bounds.y) / bounds.height).clamp(0.0, 1.0);
+
+        Message::SaturationValue(saturation, value)
+    }
+}
+
+impl canvas::Program<Message> for SaturationValue {
+    /// Whether the mouse is being dragged across the square.
+    type State = bool;
+
+    fn update(
+        &self,
+        dragging: &mut bool,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if let Some(position) = cursor.position_over(bounds) {
+                    *dragging = true;
+                    return (event::Status::Captured, Some(Self::pick(bounds, position)));
+                }
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { position }) if *dragging => {
+                return (event::Status::Captured, Some(Self::pick(bounds, position)));
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                *dragging = false;
+            }
+            _ => {}
+        }
+
+        (event::Status::Ignored, None)
+    }
+
+    fn draw(
+        &self,
+        _dragging: &bool,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = frame.size();
+
+        let hue: Color = Srgb::from_color(Hsv::new(self.hue, 1.0, 1.0)).into();
+
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            size,
+            gradient::Linear::new(Point::ORIGIN, Point::new(size.width, 0.0))
+                .add_stop(0.0, Color::WHITE)
+                .add_stop(1.0, hue),
+        );
+
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            size,
+            gradient::Linear::new(Point::ORIGIN, Point::new(0.0, size.height))
+                .add_stop(0.0, Color::TRANSPARENT)
+                .add_stop(1.0, Color::BLACK),
+        );
+
+        let marker = Point::new(
+            self.saturation * size.width,
+            (1.0 - self.value) * size.height,
+        );
+
+        frame.stroke(
+            &Path::circle(marker, 5.0),
+            Stroke::default().with_color(Color::WHITE).with_width(2.0),
+        );
+        frame.stroke(
+            &Path::circle(marker, 6.5),
+            Stroke::default().with_color(Color::BLACK).with_width(1.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _dragging: &bool,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
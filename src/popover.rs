@@ -0,0 +1,294 @@
+//! An interactive popup anchored below (or above) another widget.
+use iced::{
+    Element, Event, Length, Point, Rectangle, Size, Vector,
+    advanced::{
+        Clipboard, Layout, Shell, Widget, layout, mouse, overlay, renderer,
+        widget::{Operation, Tree},
+    },
+    event,
+};
+
+/// The space between the anchor and its popup.
+const GAP: f32 = 4.0;
+
+pub struct Popover<'a, Message> {
+    content: Element<'a, Message>,
+    popup: Option<Element<'a, Message>>,
+    on_dismiss: Option<Message>,
+}
+
+pub fn popover<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    popup: Option<Element<'a, Message>>,
+) -> Popover<'a, Message> {
+    Popover {
+        content: content.into(),
+        popup,
+        on_dismiss: None,
+    }
+}
+
+impl<Message> Popover<'_, Message> {
+    /// Sets the message produced when the user clicks outside of the popup.
+    pub fn on_dismiss(mut self, message: Message) -> Self {
+        self.on_dismiss = Some(message);
+        self
+    }
+}
+
+impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for Popover<'_, Message> {
+    fn children(&self) -> Vec<Tree> {
+        std::iter::once(&self.content)
+            .chain(&self.popup)
+            .map(Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let children: Vec<_> = std::iter::once(&self.content)
+            .chain(&self.popup)
+            .map(Element::as_widget)
+            .collect();
+
+        tree.diff_children(&children);
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, iced::Theme, iced::Renderer>> {
+        let (content_tree, popup_tree) = tree.children.split_at_mut(1);
+
+        let content = self.content.as_widget_mut().overlay(
+            &mut content_tree[0],
+            layout,
+            renderer,
+            translation,
+        );
+
+        let popup = self
+            .popup
+            .as_mut()
+            .zip(popup_tree.first_mut())
+            .map(|(popup, state)| {
+                overlay::Element::new(Box::new(Overlay {
+                    popup,
+                    state,
+                    anchor: layout.bounds() + translation,
+                    on_dismiss: self.on_dismiss.clone(),
+                }))
+            });
+
+        if content.is_some() || popup.is_some() {
+            Some(
+                overlay::Group::with_children(content.into_iter().chain(popup).collect()).overlay(),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, Message: Clone + 'a> From<Popover<'a, Message>> for Element<'a, Message> {
+    fn from(popover: Popover<'a, Message>) -> Self {
+        Element::new(popover)
+    }
+}
+
+struct Overlay<'a, 'b, Message> {
+    popup: &'b mut Element<'a, Message>,
+    state: &'b mut Tree,
+    anchor: Rectangle,
+    on_dismiss: Option<Message>,
+}
+
+impl<Message: Clone> overlay::Overlay<Message, iced::Theme, iced::Renderer>
+    for Overlay<'_, '_, Message>
+{
+    fn layout(&mut self, renderer: &iced::Renderer, bounds: Size) -> layout::Node {
+        let node = self.popup.as_widget().layout(
+            self.state,
+            renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+
+        let size = node.size();
+
+        let below = self.anchor.y + self.anchor.height + GAP;
+        let above = self.anchor.y - GAP - size.height;
+
+        let y = if below + size.height <= bounds.height || above < 0.0 {
+            below.min(bounds.height - size.height).max(0.0)
+        } else {
+            above
+        };
+
+        let x = self.anchor.x.min(bounds.width - size.width).max(0.0);
+
+        node.move_to(Point::new(x, y))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut iced::Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        self.popup.as_widget().draw(
+            self.state,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            &layout.bounds(),
+        );
+    }
+
+    fn operate(
+        &mut self,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.popup
+            .as_widget()
+            .operate(self.state, layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        if let Event::Mouse(mouse::Event::ButtonPressed(_)) = event {
+            let outside = !cursor.is_over(layout.bounds()) && !cursor.is_over(self.anchor);
+
+            if let Some(on_dismiss) = self.on_dismiss.clone().filter(|_| outside) {
+                // Let the click through, so that it can also open another popup
+                shell.publish(on_dismiss);
+                return event::Status::Ignored;
+            }
+        }
+
+        self.popup.as_widget_mut().on_event(
+            self.state,
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            &layout.bounds(),
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        self.popup
+            .as_widget()
+            .mouse_interaction(self.state, layout, cursor, viewport, renderer)
+    }
+}
@@ -0,0 +1,129 @@
+//! A row's color at evenly spaced OKLCH lightness levels, to pick lighter or
+//! darker shades of it from.
+use crate::{Usage, Variant, color, pair};
+use iced::{Color, theme::palette::Extended};
+
+/// How many steps a ramp has.
+pub const STEPS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub color: Color,
+    /// The variants whose color is closest to this step in lightness.
+    pub variants: Vec<Variant>,
+}
+
+/// The ramp of a row, keeping the chroma and the hue of its base color.
+pub fn ramp(palette: &Extended, usage: Usage) -> Vec<Step> {
+    let base = color::to_oklch(pair(palette, usage, Variant::Base).color);
+    let hue = base.hue.into_positive_degrees();
+
+    let mut steps: Vec<_> = (0..STEPS)
+        .map(|index| Step {
+            color: color::from_oklch(lightness(index), base.chroma, hue),
+            variants: Vec::new(),
+        })
+        .collect();
+
+    for variant in Variant::ALL {
+        let lightness = color::to_oklch(pair(palette, usage, variant).color).l;
+        let index = ((lightness * STEPS as f32) as usize).min(STEPS - 1);
+
+        steps[index].variants.push(variant);
+    }
+
+    steps
+}
+
+/// The lightness of a step, in the middle of its share of the range.
+fn lightness(index: usize) -> f32 {
+    (index as f32 + 0.5) / STEPS as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_themes;
+    use iced::Theme;
+
+    #[test]
+    fn steps_are_evenly_spaced() {
+        assert_eq!(
+            (0..STEPS).map(lightness).collect::<Vec<_>>(),
+            [0.05, 0.15, 0.25, 0.35, 0.45, 0.55, 0.65, 0.75, 0.85, 0.95]
+        );
+
+        // A gray row has no chroma to clip, so every step has its lightness
+        let steps = ramp(Theme::Light.extended_palette(), Usage::Background);
+
+        assert_eq!(steps.len(), STEPS);
+
+        for (index, step) in steps.iter().enumerate() {
+            let oklch = color::to_oklch(step.color);
+
+            assert!((oklch.l - lightness(index)).abs() < 1e-3, "step {index}");
+            assert!(oklch.chroma < 1e-3, "step {index}");
+        }
+    }
+
+    #[test]
+    fn steps_keep_the_hue() {
+        let palette = Theme::Light.extended_palette();
+        let base = palette.primary.base.color;
+
+        // The lightest and darkest steps are clipped into sRGB the most
+        for step in &ramp(palette, Usage::Primary)[2..STEPS - 2] {
+            assert!(
+                color::hue_distance(step.color, base) < 5.0,
+                "{:?}",
+                step.color
+            );
+        }
+    }
+
+    #[test]
+    fn variants_mark_their_nearest_step() {
+        for theme in all_themes() {
+            let palette = theme.extended_palette();
+
+            for usage in Usage::ALL {
+                let steps = ramp(palette, usage);
+
+                for variant in Variant::ALL {
+                    let marked: Vec<usize> = (0..STEPS)
+                        .filter(|&index| steps[index].variants.contains(&variant))
+                        .collect();
+
+                    assert_eq!(marked.len(), 1, "{theme} {usage:?} {variant:?}");
+
+                    let l = color::to_oklch(pair(palette, usage, variant).color).l;
+                    let nearest = (0..STEPS)
+                        .min_by(|&a, &b| {
+                            (lightness(a) - l)
+                                .abs()
+                                .total_cmp(&(lightness(b) - l).abs())
+                        })
+                        .unwrap();
+
+                    assert_eq!(marked[0], nearest, "{theme} {usage:?} {variant:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extremes_mark_the_end_steps() {
+        let steps = ramp(Theme::Light.extended_palette(), Usage::Background);
+
+        // White is the lightest color there is, and lands on the last step
+        assert_eq!(steps[STEPS - 1].variants.first(), Some(&Variant::Base));
+
+        let steps = ramp(Theme::Dark.extended_palette(), Usage::Background);
+
+        assert!(
+            steps[..3]
+                .iter()
+                .any(|step| step.variants.contains(&Variant::Base))
+        );
+    }
+}
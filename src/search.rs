@@ -0,0 +1,276 @@
+//! Finding a theme by typing part of its name, one of its tags or the colors
+//! it is made of.
+use crate::color;
+use iced::theme::palette::Extended;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, fmt, path::PathBuf};
+
+/// How many recently viewed themes are remembered.
+pub const RECENT: usize = 8;
+
+/// A theme which can be searched for: a built-in one by name, or a theme of
+/// the library by its path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeId {
+    BuiltIn(String),
+    Entry(PathBuf),
+}
+
+/// A command palette being typed into.
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    pub query: String,
+    /// The index of the highlighted result.
+    pub selected: usize,
+}
+
+/// A theme as the search sees it.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub id: ThemeId,
+    pub name: String,
+    /// The theme's tags, followed by the words describing its colors.
+    pub keywords: Vec<String>,
+    pub palette: Extended,
+}
+
+impl Candidate {
+    pub fn new(id: ThemeId, name: String, tags: &[String], palette: Extended) -> Self {
+        let keywords = tags
+            .iter()
+            .cloned()
+            .chain(describe(&palette).into_iter().map(str::to_owned))
+            .collect();
+
+        Self {
+            id,
+            name,
+            keywords,
+            palette,
+        }
+    }
+
+    /// How well the candidate matches the query, if every word of it is
+    /// found in either the name or a keyword.
+    pub fn score(&self, query: &str) -> Option<u32> {
+        query
+            .split_whitespace()
+            .map(|word| {
+                std::iter::once(&self.name)
+                    .chain(&self.keywords)
+                    .filter_map(|text| score(word, text))
+                    .max()
+            })
+            .sum()
+    }
+}
+
+/// Where a result is listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    Favorites,
+    Recent,
+    Themes,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Section::Favorites => "Favorites",
+            Section::Recent => "Recently viewed",
+            Section::Themes => "Themes",
+        })
+    }
+}
+
+/// The candidates matching the query, best first within each section.
+/// Recently viewed themes are only listed apart while nothing is typed.
+pub fn results(
+    candidates: Vec<Candidate>,
+    query: &str,
+    favorites: &[ThemeId],
+    recent: &[ThemeId],
+) -> Vec<(Section, Candidate)> {
+    let browsing = query.trim().is_empty();
+
+    let mut results: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let score = candidate.score(query)?;
+            let recently = recent.iter().position(|id| *id == candidate.id);
+
+            let (section, order) = if favorites.contains(&candidate.id) {
+                (Section::Favorites, 0)
+            } else if let Some(order) = recently.filter(|_| browsing) {
+                (Section::Recent, order)
+            } else {
+                (Section::Themes, 0)
+            };
+
+            Some((section, Reverse(score), order, candidate))
+        })
+        .collect();
+
+    results.sort_by_key(|(section, score, order, _)| (*section, *score, *order));
+
+    results
+        .into_iter()
+        .map(|(section, _, _, candidate)| (section, candidate))
+        .collect()
+}
+
+/// How well `query` matches `text`, when every character of the query
+/// appears in it in order. Characters starting a word, and runs of
+/// consecutive characters, are worth more.
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut start = 0;
+    let mut previous = None;
+
+    for wanted in query.to_lowercase().chars() {
+        let index = start + text[start..].iter().position(|&c| c == wanted)?;
+
+        score += 1;
+
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+
+        previous = Some(index);
+        start = index + 1;
+    }
+
+    Some(score)
+}
+
+/// Words for how a palette looks: whether it is dark or light, and the
+/// hues of its background and primary colors.
+pub fn describe(palette: &Extended) -> Vec<&'static str> {
+    let mut words = vec![if palette.is_dark { "dark" } else { "light" }];
+
+    for color in [palette.background.base.color, palette.primary.base.color] {
+        if let Some(hue) = color::hue_name(color).filter(|hue| !words.contains(hue)) {
+            words.push(hue);
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Theme;
+
+    fn candidate(theme: Theme, tags: &[&str]) -> Candidate {
+        let tags: Vec<String> = tags.iter().map(|tag| (*tag).to_owned()).collect();
+
+        Candidate::new(
+            ThemeId::BuiltIn(theme.to_string()),
+            theme.to_string(),
+            &tags,
+            *theme.extended_palette(),
+        )
+    }
+
+    fn id(theme: Theme) -> ThemeId {
+        ThemeId::BuiltIn(theme.to_string())
+    }
+
+    fn names(results: &[(Section, Candidate)]) -> Vec<(Section, &str)> {
+        results
+            .iter()
+            .map(|(section, candidate)| (*section, candidate.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn scores() {
+        assert_eq!(score("", "Dracula"), Some(0));
+        assert_eq!(score("dr", "Dracula"), score("DR", "dracula"));
+        assert_eq!(score("ad", "Dracula"), None);
+        assert_eq!(score("x", "Dracula"), None);
+
+        // Consecutive characters beat scattered ones, and word starts beat
+        // both
+        assert!(score("dr", "Dracula") > score("dc", "Dracula"));
+        assert!(score("dr", "Dracula") > score("ul", "Dracula"));
+        assert!(score("k", "Knight") > score("k", "Dark"));
+    }
+
+    #[test]
+    fn every_word_must_match() {
+        let nord = candidate(Theme::Nord, &["arctic"]);
+
+        assert!(nord.score("nord dark").is_some());
+        assert!(nord.score("arc").is_some());
+        assert_eq!(nord.score("nord light"), None);
+    }
+
+    #[test]
+    fn ranking() {
+        let named = |name: &str| Candidate {
+            id: ThemeId::Entry(PathBuf::from(name)),
+            name: name.to_owned(),
+            ..candidate(Theme::Light, &[])
+        };
+        let candidates = vec![
+            named("Primrose"),
+            named("Midnight"),
+            named("Aurora"),
+            named("Rose"),
+        ];
+
+        assert_eq!(
+            names(&results(candidates, "ro", &[], &[])),
+            vec![
+                (Section::Themes, "Rose"),
+                (Section::Themes, "Aurora"),
+                (Section::Themes, "Primrose"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let candidates = || {
+            vec![
+                candidate(Theme::Light, &[]),
+                candidate(Theme::Dark, &[]),
+                candidate(Theme::Nord, &[]),
+                candidate(Theme::Dracula, &[]),
+            ]
+        };
+        let favorites = [id(Theme::Nord)];
+        let recent = [id(Theme::Dracula), id(Theme::Nord), id(Theme::Light)];
+
+        // Favorites come first, then the themes viewed last, most recent
+        // first
+        assert_eq!(
+            names(&results(candidates(), "", &favorites, &recent)),
+            vec![
+                (Section::Favorites, "Nord"),
+                (Section::Recent, "Dracula"),
+                (Section::Recent, "Light"),
+                (Section::Themes, "Dark"),
+            ]
+        );
+
+        // Once something is typed, recent themes are ranked with the others
+        assert_eq!(
+            names(&results(candidates(), "d", &favorites, &recent)),
+            vec![
+                (Section::Favorites, "Nord"),
+                (Section::Themes, "Dark"),
+                (Section::Themes, "Dracula"),
+            ]
+        );
+    }
+}
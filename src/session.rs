@@ -0,0 +1,167 @@
+//! The state of the viewer, saved whenever it changes and restored the next
+//! time the viewer is opened.
+use crate::{
+    Usage, Variant,
+    search::ThemeId,
+    theme_file::{Error, ThemeFile},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// The name of the selected built-in theme.
+    pub theme: Option<String>,
+    /// The library theme being viewed.
+    pub entry: Option<PathBuf>,
+    pub custom: Option<ThemeFile>,
+    pub locked: Vec<(Usage, Variant)>,
+    pub pending: Option<Pending>,
+    /// The custom themes each undo goes back to, the latest last. `None`
+    /// stands for the built-in theme.
+    pub undo: Vec<Option<ThemeFile>>,
+    pub redo: Vec<Option<ThemeFile>>,
+    pub favorites: Vec<ThemeId>,
+    /// The themes last viewed, the latest first.
+    pub recent_themes: Vec<ThemeId>,
+}
+
+/// A cell being typed into, which was not applied yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pending {
+    pub usage: Usage,
+    pub variant: Variant,
+    pub input: String,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config_directory().map(|config| config.join("session.json"))
+}
+
+/// Reads the last session, which is empty if there was none.
+pub fn load(path: &Path) -> Result<Session, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            serde_json::from_str(&contents).map_err(|error| Error::Parse(error.to_string()))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Session::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn to_json(session: &Session) -> Result<String, Error> {
+    serde_json::to_string(session).map_err(|error| Error::Encode(error.to_string()))
+}
+
+/// Replaces the saved session. The contents are written to a temporary file
+/// first, so that a crash while saving leaves the previous session intact.
+pub async fn save(path: PathBuf, contents: String) -> Result<(), Error> {
+    if let Some(directory) = path.parent() {
+        tokio::fs::create_dir_all(directory).await?;
+    }
+
+    let temporary = path.with_extension("json.tmp");
+    tokio::fs::write(&temporary, contents).await?;
+
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(&temporary)
+        .await?
+        .sync_all()
+        .await?;
+
+    tokio::fs::rename(&temporary, &path).await?;
+
+    // The rename itself is only durable once the directory is synced
+    #[cfg(unix)]
+    if let Some(directory) = path.parent() {
+        tokio::fs::File::open(directory).await?.sync_all().await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Theme;
+
+    // Colors are stored as hex, so start from what a file would hold
+    fn file(theme: &Theme) -> ThemeFile {
+        let file = ThemeFile::new(theme.to_string(), theme.extended_palette(), &[]);
+
+        serde_json::from_str(&serde_json::to_string(&file).unwrap()).unwrap()
+    }
+
+    fn temporary(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("iced_theme_viewer-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let session = Session {
+            theme: Some("Dracula".to_owned()),
+            entry: Some(PathBuf::from("/themes/mine.json")),
+            custom: Some(file(&Theme::Nord)),
+            locked: vec![(Usage::Primary, Variant::Weak)],
+            pending: Some(Pending {
+                usage: Usage::Danger,
+                variant: Variant::Strong,
+                input: "#ff00".to_owned(),
+            }),
+            undo: vec![None, Some(file(&Theme::Light))],
+            redo: vec![Some(file(&Theme::Dark))],
+            favorites: vec![ThemeId::BuiltIn("Nord".to_owned())],
+            recent_themes: vec![ThemeId::Entry(PathBuf::from("/themes/mine.json"))],
+        };
+
+        let path = temporary("round-trip.json");
+        std::fs::write(&path, to_json(&session).unwrap()).unwrap();
+        let loaded = load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.unwrap(), session);
+    }
+
+    #[test]
+    fn older_sessions() {
+        // Written before favorites, recent themes and locked cells existed
+        let custom = serde_json::to_string(&file(&Theme::Nord)).unwrap();
+        let contents = format!(
+            r#"{{"theme":"Nord","entry":null,"custom":{custom},"pending":null,"undo":[null],"redo":[]}}"#
+        );
+
+        let path = temporary("older.json");
+        std::fs::write(&path, contents).unwrap();
+        let loaded = load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let session = loaded.unwrap();
+
+        assert_eq!(session.theme.as_deref(), Some("Nord"));
+        assert_eq!(session.undo, vec![None]);
+        assert!(session.favorites.is_empty());
+        assert!(session.recent_themes.is_empty());
+        assert!(session.locked.is_empty());
+        assert_eq!(session.custom, Some(file(&Theme::Nord)));
+    }
+
+    #[test]
+    fn missing_and_invalid_sessions() {
+        assert_eq!(
+            load(&temporary("missing.json")).unwrap(),
+            Session::default()
+        );
+
+        let path = temporary("invalid.json");
+        std::fs::write(&path, "{\"theme\": ").unwrap();
+        let loaded = load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(loaded, Err(Error::Parse(_))));
+    }
+}
@@ -0,0 +1,460 @@
+//! A swatch sheet of the whole grid, rendered to SVG or, without a GPU, to
+//! PNG.
+use crate::{Usage, Variant, color, pair};
+use iced::{
+    Color, Point, Rectangle, Size, Theme,
+    advanced::graphics::text::cosmic_text::{
+        self, Attrs, Buffer, Family, FontSystem, Metrics, Shaping, SwashCache,
+    },
+};
+use std::fmt;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+const PADDING: f32 = 16.0;
+const SPACING: f32 = 8.0;
+const TITLE_HEIGHT: f32 = 40.0;
+const HEADER_HEIGHT: f32 = 24.0;
+const LABEL_WIDTH: f32 = 110.0;
+const CELL_WIDTH: f32 = 150.0;
+const CELL_HEIGHT: f32 = 64.0;
+const RADIUS: f32 = 10.0;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// There was nothing to draw.
+    Empty,
+    /// The PNG could not be written out.
+    Encode(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "the sheet is empty"),
+            Error::Encode(error) => write!(f, "encoding failed: {error}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Rectangle {
+        bounds: Rectangle,
+        color: Color,
+        border: Option<Color>,
+        radius: f32,
+    },
+    /// Text vertically centered on `position`.
+    Text {
+        position: Point,
+        content: String,
+        size: f32,
+        color: Color,
+        centered: bool,
+    },
+}
+
+/// The grid of one or more themes, laid out one below the other.
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    size: Size,
+    shapes: Vec<Shape>,
+}
+
+impl Sheet {
+    pub fn new<'a>(themes: impl IntoIterator<Item = &'a Theme>) -> Self {
+        let columns = Variant::ALL.len() as f32;
+        let rows = Usage::ALL.len() as f32;
+
+        let width = 2.0 * PADDING + LABEL_WIDTH + columns * (CELL_WIDTH + SPACING) - SPACING;
+        let height =
+            2.0 * PADDING + TITLE_HEIGHT + HEADER_HEIGHT + rows * (CELL_HEIGHT + SPACING) - SPACING;
+
+        let mut shapes = Vec::new();
+        let mut y = 0.0;
+
+        for theme in themes {
+            let extended = theme.extended_palette();
+            let background = extended.background.base;
+
+            shapes.push(Shape::Rectangle {
+                bounds: Rectangle::new(Point::new(0.0, y), Size::new(width, height)),
+                color: background.color,
+                border: None,
+                radius: 0.0,
+            });
+
+            let label = |x, y, content: &str, size, centered| Shape::Text {
+                position: Point::new(x, y),
+                content: content.to_owned(),
+                size,
+                color: background.text,
+                centered,
+            };
+
+            let mut top = y + PADDING;
+            shapes.push(label(
+                PADDING,
+                top + TITLE_HEIGHT / 2.0,
+                &theme.to_string(),
+                22.0,
+                false,
+            ));
+            top += TITLE_HEIGHT;
+
+            let column =
+                |index: usize| PADDING + LABEL_WIDTH + index as f32 * (CELL_WIDTH + SPACING);
+
+            for (index, variant) in Variant::ALL.into_iter().enumerate() {
+                let x = column(index) + CELL_WIDTH / 2.0;
+                shapes.push(label(
+                    x,
+                    top + HEADER_HEIGHT / 2.0,
+                    variant.label(),
+                    14.0,
+                    true,
+                ));
+            }
+            top += HEADER_HEIGHT;
+
+            for usage in Usage::ALL {
+                let middle = top + CELL_HEIGHT / 2.0;
+                shapes.push(label(PADDING, middle, usage.label(), 16.0, false));
+
+                for (index, variant) in Variant::ALL.into_iter().enumerate() {
+                    let pair = pair(extended, usage, variant);
+                    let x = column(index);
+
+                    shapes.push(Shape::Rectangle {
+                        bounds: Rectangle::new(
+                            Point::new(x, top),
+                            Size::new(CELL_WIDTH, CELL_HEIGHT),
+                        ),
+                        color: pair.color,
+                        border: Some(extended.background.strong.color),
+                        radius: RADIUS,
+                    });
+
+                    let text = |offset, content, size| Shape::Text {
+                        position: Point::new(x + CELL_WIDTH / 2.0, middle + offset),
+                        content,
+                        size,
+                        color: pair.text,
+                        centered: true,
+                    };
+
+                    shapes.push(text(-9.0, color::to_hex(pair.color), 15.0));
+                    shapes.push(text(
+                        11.0,
+                        format!("{:.2}:1", color::contrast(pair.color, pair.text)),
+                        12.0,
+                    ));
+                }
+
+                top += CELL_HEIGHT + SPACING;
+            }
+
+            y += height;
+        }
+
+        Self {
+            size: Size::new(width, y),
+            shapes,
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let Size { width, height } = self.size;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\">\n"
+        );
+
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rectangle {
+                    bounds,
+                    color,
+                    border,
+                    radius,
+                } => {
+                    let stroke = border
+                        .map(|border| format!(" stroke=\"{}\"", color::to_hex(border)))
+                        .unwrap_or_default();
+
+                    svg.push_str(&format!(
+                        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{radius}\" {}{stroke}/>\n",
+                        bounds.x,
+                        bounds.y,
+                        bounds.width,
+                        bounds.height,
+                        fill(*color),
+                    ));
+                }
+                Shape::Text {
+                    position,
+                    content,
+                    size,
+                    color,
+                    centered,
+                } => {
+                    let anchor = if *centered { "middle" } else { "start" };
+
+                    svg.push_str(&format!(
+                        "  <text x=\"{}\" y=\"{}\" font-size=\"{size}\" text-anchor=\"{anchor}\" \
+                         dominant-baseline=\"central\" {}>{}</text>\n",
+                        position.x,
+                        position.y,
+                        fill(*color),
+                        escape(content),
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Rasterizes the sheet on the CPU, using the fonts installed on the
+    /// system.
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        let mut pixmap =
+            Pixmap::new(self.size.width as u32, self.size.height as u32).ok_or(Error::Empty)?;
+
+        let mut font_system = FontSystem::new();
+        let mut cache = SwashCache::new();
+
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rectangle {
+                    bounds,
+                    color,
+                    border,
+                    radius,
+                } => {
+                    let Some(path) = rounded_rectangle(*bounds, *radius) else {
+                        continue;
+                    };
+
+                    let mut paint = Paint::default();
+                    paint.set_color(skia_color(*color));
+                    paint.anti_alias = true;
+
+                    pixmap.fill_path(
+                        &path,
+                        &paint,
+                        FillRule::Winding,
+                        Transform::identity(),
+                        None,
+                    );
+
+                    if let Some(border) = border {
+                        paint.set_color(skia_color(*border));
+                        pixmap.stroke_path(
+                            &path,
+                            &paint,
+                            &Stroke::default(),
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                Shape::Text {
+                    position,
+                    content,
+                    size,
+                    color,
+                    centered,
+                } => {
+                    let mut buffer = Buffer::new(&mut font_system, Metrics::relative(*size, 1.2));
+                    buffer.set_text(
+                        &mut font_system,
+                        content,
+                        Attrs::new().family(Family::SansSerif),
+                        Shaping::Advanced,
+                    );
+                    buffer.shape_until_scroll(&mut font_system, false);
+
+                    let (width, height) = buffer
+                        .layout_runs()
+                        .fold((0.0f32, 0.0f32), |(width, height), run| {
+                            (width.max(run.line_w), height + run.line_height)
+                        });
+
+                    let x = if *centered {
+                        position.x - width / 2.0
+                    } else {
+                        position.x
+                    };
+                    let y = position.y - height / 2.0;
+
+                    let [r, g, b, a] = color.into_rgba8();
+
+                    buffer.draw(
+                        &mut font_system,
+                        &mut cache,
+                        cosmic_text::Color::rgba(r, g, b, a),
+                        |glyph_x, glyph_y, _, _, color| {
+                            blend(
+                                &mut pixmap,
+                                x.round() as i32 + glyph_x,
+                                y.round() as i32 + glyph_y,
+                                color,
+                            );
+                        },
+                    );
+                }
+            }
+        }
+
+        pixmap
+            .encode_png()
+            .map_err(|error| Error::Encode(error.to_string()))
+    }
+}
+
+fn fill(color: Color) -> String {
+    let opaque = Color { a: 1.0, ..color };
+
+    if color.a < 1.0 {
+        format!(
+            "fill=\"{}\" fill-opacity=\"{:.3}\"",
+            color::to_hex(opaque),
+            color.a
+        )
+    } else {
+        format!("fill=\"{}\"", color::to_hex(opaque))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn skia_color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(color.r, color.g, color.b, color.a)
+        .unwrap_or(tiny_skia::Color::BLACK)
+}
+
+fn rounded_rectangle(bounds: Rectangle, radius: f32) -> Option<tiny_skia::Path> {
+    let Rectangle {
+        x,
+        y,
+        width,
+        height,
+    } = bounds;
+    let radius = radius.min(width / 2.0).min(height / 2.0);
+
+    let mut path = PathBuilder::new();
+    path.move_to(x + radius, y);
+    path.line_to(x + width - radius, y);
+    path.quad_to(x + width, y, x + width, y + radius);
+    path.line_to(x + width, y + height - radius);
+    path.quad_to(x + width, y + height, x + width - radius, y + height);
+    path.line_to(x + radius, y + height);
+    path.quad_to(x, y + height, x, y + height - radius);
+    path.line_to(x, y + radius);
+    path.quad_to(x, y, x + radius, y);
+    path.close();
+
+    path.finish()
+}
+
+/// Draws a single pixel of a glyph, whose alpha is how much of the pixel
+/// the glyph covers.
+fn blend(pixmap: &mut Pixmap, x: i32, y: i32, color: cosmic_text::Color) {
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+
+    if color.a() == 0 || x < 0 || y < 0 || x >= width || y >= height {
+        return;
+    }
+
+    let alpha = f32::from(color.a()) / 255.0;
+    let pixel = &mut pixmap.pixels_mut()[(y * width + x) as usize];
+
+    // Pixels are stored with premultiplied alpha
+    let mix = |source: u8, destination: u8| {
+        (f32::from(source) * alpha + f32::from(destination) * (1.0 - alpha)).round() as u8
+    };
+
+    let blended = tiny_skia::PremultipliedColorU8::from_rgba(
+        mix(color.r(), pixel.red()),
+        mix(color.g(), pixel.green()),
+        mix(color.b(), pixel.blue()),
+        mix(u8::MAX, pixel.alpha()),
+    );
+
+    if let Some(blended) = blended {
+        *pixel = blended;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_has_every_cell() {
+        let theme = Theme::Nord;
+        let svg = Sheet::new([&theme]).to_svg();
+        let extended = theme.extended_palette();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">Nord</text>"));
+
+        for variant in Variant::ALL {
+            assert!(svg.contains(&format!(">{}</text>", variant.label())));
+        }
+
+        for usage in Usage::ALL {
+            assert!(svg.contains(&format!(">{}</text>", usage.label())));
+
+            for variant in Variant::ALL {
+                let hex = color::to_hex(pair(extended, usage, variant).color);
+
+                assert!(
+                    svg.contains(&format!(
+                        "width=\"{CELL_WIDTH}\" height=\"{CELL_HEIGHT}\" rx=\"{RADIUS}\" fill=\"{hex}\""
+                    )),
+                    "{usage:?} {variant:?} swatch"
+                );
+                assert!(
+                    svg.contains(&format!(">{hex}</text>")),
+                    "{usage:?} {variant:?} hex"
+                );
+            }
+        }
+
+        // The background, and a swatch for every cell
+        assert_eq!(svg.matches("<rect ").count(), 1 + 15);
+    }
+
+    #[test]
+    fn png_decodes() {
+        let themes = [Theme::Light, Theme::Dark];
+        let sheet = Sheet::new(&themes);
+
+        let png = Pixmap::decode_png(&sheet.to_png().unwrap()).unwrap();
+
+        // One grid below the other
+        assert_eq!((png.width(), png.height()), (608, 2 * 448));
+
+        // The top left corner is Light's background
+        let corner = png.pixel(0, 0).unwrap().demultiply();
+        let background = Theme::Light.extended_palette().background.base.color;
+        assert_eq!(
+            [corner.red(), corner.green(), corner.blue(), corner.alpha()],
+            background.into_rgba8()
+        );
+    }
+
+    #[test]
+    fn empty_sheets() {
+        assert!(matches!(Sheet::new([]).to_png(), Err(Error::Empty)));
+    }
+}
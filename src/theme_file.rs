@@ -0,0 +1,202 @@
+//! The viewer's own JSON theme format.
+use crate::{CUSTOM, Usage, Variant, pair, updated_extended};
+use iced::{
+    Color,
+    theme::{
+        Palette,
+        palette::{Extended, Pair},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeFile {
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    pub is_dark: bool,
+    pub background: Group,
+    pub primary: Group,
+    pub secondary: Group,
+    pub success: Group,
+    pub danger: Group,
+    /// Cells kept as they are when the palette is regenerated.
+    #[serde(default)]
+    pub locked: Vec<(Usage, Variant)>,
+}
+
+/// What a theme is called, who made it and what it is like.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default = "default_name")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Metadata {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    /// Splits tags separated by commas, as they are typed.
+    pub fn parse_tags(tags: &str) -> Vec<String> {
+        tags.split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub base: PairColors,
+    pub weak: PairColors,
+    pub strong: PairColors,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PairColors {
+    #[serde(with = "hex")]
+    pub color: Color,
+    #[serde(with = "hex")]
+    pub text: Color,
+}
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Io(io::ErrorKind),
+    Parse(String),
+    /// A theme could not be written out.
+    Encode(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(kind) => write!(f, "{kind}"),
+            Error::Parse(error) => write!(f, "{error}"),
+            Error::Encode(error) => write!(f, "encoding failed: {error}"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error.kind())
+    }
+}
+
+impl ThemeFile {
+    pub fn new(name: String, extended: &Extended, locked: &[(Usage, Variant)]) -> Self {
+        let group = |usage| {
+            let colors = |variant| {
+                let pair = pair(extended, usage, variant);
+                PairColors {
+                    color: pair.color,
+                    text: pair.text,
+                }
+            };
+
+            Group {
+                base: colors(Variant::Base),
+                weak: colors(Variant::Weak),
+                strong: colors(Variant::Strong),
+            }
+        };
+
+        Self {
+            metadata: Metadata::new(name),
+            is_dark: extended.is_dark,
+            background: group(Usage::Background),
+            primary: group(Usage::Primary),
+            secondary: group(Usage::Secondary),
+            success: group(Usage::Success),
+            danger: group(Usage::Danger),
+            locked: locked.to_vec(),
+        }
+    }
+
+    pub fn extended(&self) -> Extended {
+        let initial = Extended {
+            is_dark: self.is_dark,
+            ..Extended::generate(Palette::DARK)
+        };
+
+        Usage::ALL.into_iter().fold(initial, |extended, usage| {
+            let group = match usage {
+                Usage::Background => self.background,
+                Usage::Primary => self.primary,
+                Usage::Secondary => self.secondary,
+                Usage::Success => self.success,
+                Usage::Danger => self.danger,
+            };
+
+            [
+                (Variant::Base, group.base),
+                (Variant::Weak, group.weak),
+                (Variant::Strong, group.strong),
+            ]
+            .into_iter()
+            .fold(extended, |extended, (variant, colors)| {
+                let pair = Pair {
+                    color: colors.color,
+                    text: colors.text,
+                };
+
+                updated_extended(extended, pair, usage, variant)
+            })
+        })
+    }
+}
+
+pub fn parse(contents: &str) -> Result<ThemeFile, Error> {
+    serde_json::from_str(contents).map_err(|error| Error::Parse(error.to_string()))
+}
+
+pub fn to_json(file: &ThemeFile) -> Result<String, Error> {
+    serde_json::to_string_pretty(file).map_err(|error| Error::Encode(error.to_string()))
+}
+
+/// The file on a single line, as it is broadcast.
+pub fn to_line(file: &ThemeFile) -> Result<String, Error> {
+    serde_json::to_string(file).map_err(|error| Error::Encode(error.to_string()))
+}
+
+pub async fn save(path: PathBuf, file: ThemeFile) -> Result<PathBuf, Error> {
+    tokio::fs::write(&path, to_json(&file)?).await?;
+
+    Ok(path)
+}
+
+fn default_name() -> String {
+    CUSTOM.to_owned()
+}
+
+mod hex {
+    use crate::color::to_hex;
+    use iced::Color;
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(*color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        // `Color::parse` slices by bytes, and panics on other characters
+        hex.is_ascii()
+            .then(|| Color::parse(&hex))
+            .flatten()
+            .ok_or_else(|| de::Error::custom(format!("invalid color {hex:?}")))
+    }
+}
@@ -0,0 +1,51 @@
+//! Reloading a theme file whenever it is saved, by checking its
+//! modification time.
+use crate::{
+    import::{self, Imported},
+    theme_file::Error,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+/// How long to wait before reading a file again when it could not be
+/// parsed, since the editor saving it may not be done writing it.
+const RETRY_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone)]
+pub struct Watch {
+    pub path: PathBuf,
+    /// When the file was last loaded.
+    pub modified: Option<SystemTime>,
+}
+
+impl Watch {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            modified: None,
+        }
+    }
+
+    /// Records the modification time of the file, returning whether it
+    /// changed since the last call.
+    pub fn update(&mut self, modified: SystemTime) -> bool {
+        let changed = self.modified != Some(modified);
+        self.modified = Some(modified);
+
+        changed
+    }
+}
+
+/// Loads the file again after [`RETRY_DELAY`].
+pub async fn retry(path: PathBuf) -> Result<Imported, Error> {
+    tokio::time::sleep(RETRY_DELAY).await;
+
+    import::load(path).await
+}
+
+/// The modification time of a file, if it can be read.
+pub async fn modified(path: PathBuf) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}